@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2023::day_12_hot_springs;
+
+fuzz_target!(| data: &[u8] | {
+    // Puzzle inputs are read as text, so only valid UTF-8 reaches the parser.
+    if let Ok(data) = std::str::from_utf8(data) {
+        let _ = day_12_hot_springs::parse(data);
+    }
+});
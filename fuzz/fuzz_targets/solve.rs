@@ -0,0 +1,36 @@
+#![no_main]
+
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use libfuzzer_sys::fuzz_target;
+
+use advent_of_code_2023::{DAYS, solver};
+
+/// The longest a solver may take on an input, a solver that takes longer is reported as a crash.
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+fuzz_target!(| data: &[u8] | {
+    // The first byte picks the day, the rest is the puzzle input.
+    let Some((&day, data)) = data.split_first() else {
+        return;
+    };
+    let day = day % DAYS.end() + 1;
+
+    // Puzzle inputs are read as text, so only valid UTF-8 reaches the solver.
+    let Ok(data) = std::str::from_utf8(data) else {
+        return;
+    };
+    let data = data.to_string();
+
+    // A panic on the solving thread aborts the fuzzer, so only the time limit needs to be checked here.
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = solver(day).unwrap().solve(&data);
+        let _ = sender.send(());
+    });
+    if let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(TIME_LIMIT) {
+        panic!("day {day} did not finish within {TIME_LIMIT:?}");
+    }
+});
@@ -0,0 +1,235 @@
+#[cfg(feature = "fs")]
+use std::collections::BTreeMap;
+#[cfg(feature = "fs")]
+use std::fs;
+
+#[cfg(feature = "fs")]
+use serde::Deserialize;
+#[cfg(feature = "fs")]
+use toml::Spanned;
+
+#[cfg(feature = "fs")]
+use crate::error::{Error, Result};
+#[cfg(feature = "fs")]
+use crate::Record;
+
+/// Outcome of checking an answer against the manifest.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Verdict {
+    /// The answer is the expected answer.
+    Pass,
+    /// The answer differs from the expected answer, or the part failed to solve.
+    Fail { expected: String },
+    /// The manifest has no answer for this part.
+    Unknown
+}
+
+impl Verdict {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Verdict::Pass => "pass",
+            Verdict::Fail { .. } => "fail",
+            Verdict::Unknown => "unknown"
+        }
+    }
+
+    pub fn expected(&self) -> Option<&str> {
+        match self {
+            Verdict::Fail { expected } => Some(expected),
+            _ => None
+        }
+    }
+}
+
+
+/// An answer in the manifest, numbers may be written without quotes.
+/// `false` means that the part has no answer for this puzzle input.
+#[cfg(feature = "fs")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Answer {
+    Number(i64),
+    Text(String),
+    Solvable(bool)
+}
+
+/// What the manifest expects of a part.
+#[cfg(feature = "fs")]
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Expected {
+    /// The answer to the part.
+    Answer(String),
+    /// The part has no answer, the solver must return [`Error::Unsolvable`].
+    Unsolvable
+}
+
+#[cfg(feature = "fs")]
+type Table = BTreeMap<Spanned<String>, BTreeMap<Spanned<String>, Spanned<Answer>>>;
+
+/// The known answers to the puzzles for one set of puzzle inputs.
+/// The manifest is a TOML file with a table per day and a key per part:
+///
+/// ```toml
+/// [day_1]
+/// part_1 = 142
+/// part_2 = "281"
+///
+/// [day_20]
+/// part_2 = false  # the puzzle input has no answer to part two
+/// ```
+#[cfg(feature = "fs")]
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Manifest {
+    answers: BTreeMap<u8, [Option<Expected>; 2]>
+}
+
+#[cfg(feature = "fs")]
+impl Manifest {
+    /// Read a manifest from a TOML file.
+    pub fn load(file_path: &str) -> Result<Self> {
+        Self::parse(&fs::read_to_string(file_path)?)
+    }
+
+    /// Parse a manifest from TOML text.
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: Table = toml::from_str(text).map_err(| error | {
+            let offset = error.span().map_or(0, | span | span.start);
+            error_at(text, offset, error.message())
+        })?;
+
+        let mut answers: BTreeMap<u8, [Option<Expected>; 2]> = BTreeMap::new();
+        for (day, parts) in table {
+            let day_number = day.get_ref()
+                .strip_prefix("day_")
+                .and_then(| day | day.parse::<u8>().ok())
+                .ok_or_else(|| error_at(text, day.span().start, format!("expected \"day_<number>\", got {:?}", day.get_ref())))?;
+
+            let day_answers = answers.entry(day_number).or_default();
+            for (part, answer) in parts {
+                let index = match part.get_ref().as_str() {
+                    "part_1" => 0,
+                    "part_2" => 1,
+                    part_name => {
+                        return Err(error_at(text, part.span().start, format!("expected \"part_1\" or \"part_2\", got {part_name:?}")));
+                    }
+                };
+
+                let span = answer.span();
+                day_answers[index] = Some(match answer.into_inner() {
+                    Answer::Number(number) => Expected::Answer(number.to_string()),
+                    Answer::Text(text) => Expected::Answer(text),
+                    Answer::Solvable(false) => Expected::Unsolvable,
+                    Answer::Solvable(true) => {
+                        return Err(error_at(text, span.start, "expected an answer, or false if the part has no answer"));
+                    }
+                });
+            }
+        }
+        Ok(Self { answers })
+    }
+
+    /// What is expected of a part of a day, `part` is 0 for part one and 1 for part two.
+    pub fn expected(&self, day: u8, part: usize) -> Option<&Expected> {
+        self.answers.get(&day)?.get(part)?.as_ref()
+    }
+
+    /// Check the answers of a solved day against the manifest, each part on its own.
+    pub fn verify(&self, record: &Record) -> [Verdict; 2] {
+        [0, 1].map(| part | {
+            let answer = record.answers.as_ref().map(| answers | &answers[part]);
+            match (self.expected(record.day, part), answer) {
+                (None, _) => Verdict::Unknown,
+                (Some(Expected::Answer(expected)), Ok(Ok(answer))) if answer == expected => Verdict::Pass,
+                (Some(Expected::Unsolvable), Ok(Err(Error::Unsolvable(_)))) => Verdict::Pass,
+                (Some(Expected::Answer(expected)), _) => Verdict::Fail { expected: expected.clone() },
+                (Some(Expected::Unsolvable), _) => Verdict::Fail { expected: "no answer".to_string() }
+            }
+        })
+    }
+}
+
+
+/// A parse error at a byte offset of the text.
+#[cfg(feature = "fs")]
+fn error_at(text: &str, offset: usize, message: impl Into<String>) -> Error {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count();
+    let column = before.len() - before.rfind('\n').map_or(0, | index | index + 1);
+    Error::parse(line, column, message)
+}
+
+
+#[cfg(all(test, feature = "fs"))]
+mod tests {
+    use std::time::Duration;
+
+    use crate::solver::Timings;
+
+    use super::*;
+
+    const MANIFEST: &str = "\
+[day_2]
+part_1 = 8
+part_2 = \"2286\"
+
+[day_3]
+part_2 = 467835
+";
+
+    fn record(day: u8, answers: [&str; 2]) -> Record {
+        Record {
+            day,
+            title: "",
+            profile: None,
+            labels: ["", ""],
+            answers: Ok(answers.map(| answer | Ok(answer.to_string()))),
+            timings: Timings::default(),
+            run_time: Duration::ZERO,
+            verdicts: None,
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+
+        assert_eq!(manifest.verify(&record(2, ["8", "2286"])), [Verdict::Pass, Verdict::Pass]);
+        assert_eq!(
+            manifest.verify(&record(3, ["4361", "1"])),
+            [Verdict::Unknown, Verdict::Fail { expected: "467835".to_string() }]
+        );
+        assert_eq!(manifest.verify(&record(4, ["13", "30"])), [Verdict::Unknown, Verdict::Unknown]);
+
+        // A part that failed does not change the verdict of the other part.
+        let mut failed = record(3, ["4361", ""]);
+        failed.answers = Ok([Ok("4361".to_string()), Err(Error::unsolvable("no gears"))]);
+        assert_eq!(manifest.verify(&failed), [Verdict::Unknown, Verdict::Fail { expected: "467835".to_string() }]);
+    }
+
+    #[test]
+    fn test_verify_unsolvable() {
+        let manifest = Manifest::parse("[day_20]\npart_1 = 32000000\npart_2 = false\n").unwrap();
+
+        let mut unsolved = record(20, ["32000000", ""]);
+        unsolved.answers = Ok([Ok("32000000".to_string()), Err(Error::unsolvable("no rx"))]);
+        assert_eq!(manifest.verify(&unsolved), [Verdict::Pass, Verdict::Pass]);
+
+        let solved = record(20, ["32000000", "1"]);
+        assert_eq!(manifest.verify(&solved)[1], Verdict::Fail { expected: "no answer".to_string() });
+
+        unsolved.answers = Err(Error::parse(0, 0, "bad module"));
+        assert!(matches!(manifest.verify(&unsolved), [Verdict::Fail { .. }, Verdict::Fail { .. }]));
+
+        let error = Manifest::parse("[day_20]\npart_2 = true\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 10: expected an answer, or false if the part has no answer");
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        let error = Manifest::parse("[day_2]\npart_1 = 8\npart_3 = 1\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 1: expected \"part_1\" or \"part_2\", got \"part_3\"");
+
+        let error = Manifest::parse("[day_two]\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 2: expected \"day_<number>\", got \"day_two\"");
+    }
+}
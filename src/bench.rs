@@ -0,0 +1,246 @@
+#[cfg(all(feature = "fs", feature = "json"))]
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::solver::{Solver, Timings};
+
+/// Summary statistics of a set of run times, in nanoseconds.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub p95_ns: u64,
+    pub std_dev_ns: u64,
+}
+
+impl Stats {
+    /// Calculate the statistics of the samples, all zero if there are no samples.
+    pub fn from_samples(samples: &[Duration]) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        let mut samples: Vec<u64> = samples.iter().map(| sample | sample.as_nanos() as u64).collect();
+        samples.sort_unstable();
+        let n = samples.len();
+
+        let median = match n % 2 {
+            0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+            _ => samples[n / 2]
+        };
+
+        // Nearest-rank percentile.
+        let p95 = samples[(n * 95).div_ceil(100) - 1];
+
+        let mean: f64 = samples.iter().map(| &sample | sample as f64).sum::<f64>() / n as f64;
+        let variance: f64 = match n {
+            1 => 0.,
+            _ => samples.iter().map(| &sample | (sample as f64 - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        };
+
+        Self {
+            min_ns: samples[0],
+            median_ns: median,
+            mean_ns: mean.round() as u64,
+            p95_ns: p95,
+            std_dev_ns: variance.sqrt().round() as u64,
+        }
+    }
+}
+
+/// Run time statistics of each phase of a puzzle.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+pub struct Benchmark {
+    pub day: u8,
+    pub title: String,
+    pub runs: usize,
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+    pub total: Stats,
+    /// The error of each part that has no answer, a benchmark still measures the time it takes to find that out.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub errors: [Option<String>; 2],
+}
+
+impl Benchmark {
+    /// The statistics of each phase with its name.
+    pub fn phases(&self) -> [(&'static str, &Stats); 4] {
+        [("parse", &self.parse), ("part 1", &self.part_one), ("part 2", &self.part_two), ("total", &self.total)]
+    }
+}
+
+
+/// Solve a puzzle `warm_up` times without measuring, then `runs` times to collect the run times.
+/// Only an input that does not parse fails the benchmark, a part without an answer is reported in its errors.
+pub fn benchmark(solver: &dyn Solver, data: &str, warm_up: usize, runs: usize) -> Result<Benchmark> {
+    // The solvers are stateless, so nothing is left in a broken state after a panic.
+    let solve = || match panic::catch_unwind(AssertUnwindSafe(|| solver.solve(data))) {
+        Ok(solution) => solution,
+        Err(_) => Err(Error::Panic)
+    };
+
+    let mut errors: [Option<String>; 2] = [None, None];
+    let mut run = || solve().map(| solution | {
+        errors = solution.answers.each_ref().map(| answer | answer.as_ref().err().map(Error::to_string));
+        solution.timings
+    });
+
+    for _ in 0..warm_up {
+        run()?;
+    }
+
+    let timings: Vec<Timings> = (0..runs).map(| _ | run()).collect::<Result<_>>()?;
+    let stats = | phase: fn(&Timings) -> Duration | {
+        Stats::from_samples(&timings.iter().map(phase).collect::<Vec<Duration>>())
+    };
+
+    Ok(Benchmark {
+        day: solver.day(),
+        title: solver.title().to_string(),
+        runs,
+        parse: stats(| timings | timings.parse),
+        part_one: stats(| timings | timings.part_one),
+        part_two: stats(| timings | timings.part_two),
+        total: stats(Timings::total),
+        errors,
+    })
+}
+
+
+/// Write the benchmarks to a baseline file, replacing its contents.
+#[cfg(all(feature = "fs", feature = "json"))]
+pub fn save_baseline(file_path: &str, benchmarks: &[Benchmark]) -> Result<()> {
+    let json = serde_json::to_string_pretty(benchmarks).expect("a benchmark always serializes");
+    fs::write(file_path, json + "\n")?;
+    Ok(())
+}
+
+/// Read the benchmarks from a baseline file.
+#[cfg(all(feature = "fs", feature = "json"))]
+pub fn load_baseline(file_path: &str) -> Result<Vec<Benchmark>> {
+    serde_json::from_str(&fs::read_to_string(file_path)?).map_err(| error | {
+        // serde_json counts lines and columns from one.
+        Error::parse(error.line().saturating_sub(1), error.column().saturating_sub(1), error.to_string())
+    })
+}
+
+
+/// Relative change of the median run time compared to the baseline, e.g. 0.25 is 25% slower.
+pub fn change(current: &Stats, baseline: &Stats) -> f64 {
+    (current.median_ns as f64 - baseline.median_ns as f64) / (baseline.median_ns.max(1) as f64)
+}
+
+/// The phases whose median run time got slower than the baseline by more than `threshold`,
+/// with their relative change.
+pub fn regressions(current: &Benchmark, baseline: &Benchmark, threshold: f64) -> Vec<(&'static str, f64)> {
+    current.phases()
+        .into_iter()
+        .zip(baseline.phases())
+        .map(| ((phase, current), (_, baseline)) | (phase, change(current, baseline)))
+        .filter(| &(_, change) | change > threshold)
+        .collect()
+}
+
+
+/// Format a benchmark as a table, with the change compared to the baseline if there is one.
+pub fn report(benchmark: &Benchmark, baseline: Option<&Benchmark>, threshold: f64) -> String {
+    let duration = | ns: u64 | format!("{:?}", Duration::from_nanos(ns));
+
+    let mut text = format!("Day {}: {} ({} runs)\n", benchmark.day, benchmark.title, benchmark.runs);
+    text.push_str(&format!(
+        "{:<8}{:>12}{:>12}{:>12}{:>12}{:>12}{}\n",
+        "", "min", "median", "mean", "p95", "std dev", if baseline.is_some() { "      change" } else { "" }
+    ));
+
+    for (index, (phase, stats)) in benchmark.phases().into_iter().enumerate() {
+        text.push_str(&format!(
+            "{phase:<8}{:>12}{:>12}{:>12}{:>12}{:>12}",
+            duration(stats.min_ns),
+            duration(stats.median_ns),
+            duration(stats.mean_ns),
+            duration(stats.p95_ns),
+            duration(stats.std_dev_ns)
+        ));
+
+        if let Some(baseline) = baseline {
+            let change = change(stats, baseline.phases()[index].1);
+            text.push_str(&format!("{:>+11.1}%", change * 100.));
+            if change > threshold {
+                text.push_str("  REGRESSION");
+            }
+        }
+        text.push('\n');
+    }
+
+    for (index, error) in benchmark.errors.iter().enumerate() {
+        if let Some(error) = error {
+            text.push_str(&format!("part {} has no answer: {error}\n", index + 1));
+        }
+    }
+    text
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn micros(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().map(| &sample | Duration::from_micros(sample)).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(&micros(&[2, 4, 4, 4, 5, 5, 7, 9]));
+        assert_eq!(stats.min_ns, 2_000);
+        assert_eq!(stats.median_ns, 4_500);
+        assert_eq!(stats.mean_ns, 5_000);
+        assert_eq!(stats.p95_ns, 9_000);
+        assert_eq!(stats.std_dev_ns, 2_138);
+
+        assert_eq!(Stats::from_samples(&[]), Stats::default());
+    }
+
+    #[test]
+    fn test_regressions() {
+        let benchmark = | median_ns: u64 | {
+            let stats = Stats { median_ns, ..Stats::default() };
+            Benchmark {
+                day: 12,
+                title: "Hot Springs".to_string(),
+                runs: 10,
+                parse: Stats::default(),
+                part_one: stats,
+                part_two: Stats::default(),
+                total: stats,
+                errors: [None, None],
+            }
+        };
+
+        let baseline = benchmark(1_000);
+        assert_eq!(regressions(&benchmark(1_050), &baseline, 0.1), vec![]);
+        assert_eq!(regressions(&benchmark(1_500), &baseline, 0.1), vec![("part 1", 0.5), ("total", 0.5)]);
+        assert_eq!(regressions(&benchmark(500), &baseline, 0.1), vec![]);
+    }
+
+    #[test]
+    #[cfg(feature = "day_20")]
+    fn test_benchmark_part_without_answer() {
+        let data = "broadcaster -> a\n%a -> inv, con\n&inv -> b\n%b -> con\n&con -> output\n";
+        let benchmark = benchmark(crate::solver(20).unwrap(), data, 1, 2).unwrap();
+        assert_eq!(benchmark.errors[0], None);
+        assert!(benchmark.errors[1].as_ref().is_some_and(| error | error.contains("\"rx\"")));
+        assert!(report(&benchmark, None, 0.1).contains("part 2 has no answer"));
+
+        assert!(super::benchmark(crate::solver(20).unwrap(), "broadcaster\n", 0, 1).is_err());
+    }
+}
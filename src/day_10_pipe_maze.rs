@@ -1,97 +1,190 @@
-use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs;
 
-use crate::util::text_to_graph;
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::grid::{Grid, Position};
+use crate::util::random::Rng;
 
-pub fn determine_longest_loop_and_enclosed_tiles(file_path: &str) -> (usize, usize) {
-    let data = fs::read_to_string(file_path).unwrap();
-    let graph: HashMap<(isize, isize), u8> = text_to_graph(data);
+pub struct PipeMaze;
 
-    let start = *graph
-        .iter()
-        .find(| ((_x, _y), char) | **char == b'S')
-        .unwrap()
-        .0;  // item 0 gets the x, y coordinates
+impl Solver for PipeMaze {
+    fn day(&self) -> u8 { 10 }
 
-    // Go through the graph until we get back to the start
-    let mut position = start;
-    let mut distance: usize = 0;
-    let mut direction = get_start_direction(&graph, start.0, start.1);
-    let mut visited: HashMap<(isize, isize), usize> = HashMap::new();  // value is distance from start
+    fn title(&self) -> &'static str { "Pipe Maze" }
 
-    loop {
-        visited.insert(position, distance);
+    fn labels(&self) -> [&'static str; 2] { ["Farthest Distance", "Enclosed Tiles"] }
 
-        distance += 1;
-        match direction {
-            b'N' => position.1 -= 1,
-            b'E' => position.0 += 1,
-            b'S' => position.1 += 1,
-            b'W' => position.0 -= 1,
-            _ => unreachable!()
-        };
+    fn input_file(&self) -> &'static str { "day_10_pipe_maze.txt" }
 
-        if position == start {
-            break  // Made the full loop
-        }
-        direction = get_new_direction(&graph, &position, direction);
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn render(&self, data: &str, _part: Part) -> Option<Result<Canvas>> {
+        Some(render(data))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_longest_loop_and_enclosed_tiles(file_path: &str) -> Result<(usize, usize)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, usize)> {
+    let pipes = parse(data)?;
+    Ok((part_one(&pipes)?, part_two(&pipes)?))
+}
+
+/// The pipe tiles and the position of the start tile.
+pub struct Pipes {
+    grid: Grid<u8>,
+    start: Position
+}
+
+pub fn parse(data: &str) -> Result<Pipes> {
+    let grid: Grid<u8> = Grid::from_symbols(data, "|-LJ7F.S")?;
+
+    let start = grid
+        .position(| &tile | tile == b'S')
+        .ok_or_else(|| Error::unsolvable("there is no start tile"))?;
+
+    Ok(Pipes { grid, start })
+}
+
+/// The farthest point of the loop is halfway.
+pub fn part_one(pipes: &Pipes) -> Result<usize> {
+    let (_visited, distance) = trace_loop(pipes)?;
+    Ok(distance / 2)
+}
+
+/// Part two just go over the graph again and use the visited map for counting
+pub fn part_two(pipes: &Pipes) -> Result<usize> {
+    let (visited, distance) = trace_loop(pipes)?;
+    Ok(enclosed_tiles(&visited, distance).len())
+}
+
+/// Draw the loop with the start (S) and the tiles it encloses (I), without the pipes that are not part of it.
+#[cfg(feature = "render")]
+pub fn render(data: &str) -> Result<Canvas> {
+    let pipes = parse(data)?;
+    let (visited, distance) = trace_loop(&pipes)?;
+    let enclosed = enclosed_tiles(&visited, distance);
+
+    let mut tiles: Grid<Tile> = Grid::from_fn(visited.width(), visited.height(), | position | match visited[position] {
+        Some(0) => Tile::new('S', Style::Accent),
+        Some(_) => Tile::new(pipes.grid[position] as char, Style::Primary),
+        None => Tile::new('.', Style::Faint)
+    });
+    for &position in &enclosed {
+        tiles[position] = Tile::new('I', Style::Secondary);
     }
 
-    // Part two just go over the graph again and use the visited map for counting
-    let mut enclosed_tiles: usize = 0;
-    let ((x_max, y_max), _) = graph.iter().max().unwrap();
+    let caption = format!(
+        "A loop of {distance} pipes, the farthest is {} steps away. I: {} enclosed tiles.",
+        distance / 2,
+        enclosed.len()
+    );
+    Ok(Canvas::new(tiles, caption))
+}
+
 
-    for y in 0..*y_max {
+/// Distance from the start of each tile in the loop, None for tiles that are not in the loop.
+type Visited = Grid<Option<usize>>;
+
+/// The tiles enclosed by the loop of the given length, in row-major order.
+/// Walking along a row, the loop winds around the tiles that are enclosed.
+fn enclosed_tiles(visited: &Visited, distance: usize) -> Vec<Position> {
+    let mut enclosed_tiles: Vec<Position> = Vec::new();
+
+    // Tiles in the last row or column can not be enclosed.
+    for y in 0..visited.height() - 1 {
         let mut winding_level = 0;
-        for x in 0..*x_max {
-            if let (Some(dist_1), Some(dist_2)) = (visited.get(&(x, y)), visited.get(&(x, y + 1))) {
-                if *dist_2 == (dist_1 + 1) % distance {
+        for x in 0..visited.width() - 1 {
+            if let (Some(dist_1), Some(dist_2)) = (visited[(x, y)], visited[(x, y + 1)]) {
+                if dist_2 == (dist_1 + 1) % distance {
                     winding_level += 1
-                } else if *dist_1 == (dist_2 + 1) % distance {
+                } else if dist_1 == (dist_2 + 1) % distance {
                     winding_level -= 1
                 }
             }
 
-            if !visited.contains_key(&(x, y)) && winding_level != 0 {
-                enclosed_tiles += 1;
+            if visited[(x, y)].is_none() && winding_level != 0 {
+                enclosed_tiles.push((x, y));
             }
         }
     }
 
-    (distance / 2, enclosed_tiles)
+    enclosed_tiles
 }
 
+/// Go through the graph until we get back to the start.
+/// Returns the distance from the start of each tile in the loop and the length of the loop.
+fn trace_loop(pipes: &Pipes) -> Result<(Visited, usize)> {
+    let Pipes { grid, start } = pipes;
+
+    let mut position = *start;
+    let mut distance: usize = 0;
+    let mut direction = get_start_direction(grid, *start);
+    let mut visited: Visited = Grid::filled(grid.width(), grid.height(), None);
 
-fn get_start_direction(
-    graph: &HashMap<(isize, isize), u8>, start_x: isize, start_y: isize
-) -> u8{
+    loop {
+        visited[position] = Some(distance);
+
+        distance += 1;
+        let step = match direction {
+            b'N' => (0, -1),
+            b'E' => (1, 0),
+            b'S' => (0, 1),
+            b'W' => (-1, 0),
+            _ => return Err(Error::unsolvable("the start tile is not connected to a pipe"))
+        };
+        position = grid
+            .step(position, step)
+            .ok_or_else(|| Error::unsolvable(format!("the pipe loop leaves the grid at {position:?}")))?;
+
+        if position == *start {
+            break  // Made the full loop
+        }
+        direction = get_new_direction(grid, position, direction)
+            .ok_or_else(|| Error::unsolvable(format!("the pipe loop is broken at {position:?}")))?;
+    }
+
+    Ok((visited, distance))
+}
+
+
+fn get_start_direction(grid: &Grid<u8>, start: Position) -> u8 {
     // Ugly way to determine where we can go from the starting pipe.
+    let tile = | step: (isize, isize) | grid.step(start, step).map(| position | grid[position]);
 
     let mut start_direction = b'?';
 
-    if graph
-        .get(&(start_x, start_y - 1))
-        .is_some_and(| val | *val == b'|' || *val == b'7' || *val == b'F') {
+    if tile((0, -1)).is_some_and(| val | val == b'|' || val == b'7' || val == b'F') {
 
         start_direction = b'N';
 
-    } else if graph
-        .get(&(start_x -  1, start_y))
-        .is_some_and(| val | *val == b'-' || *val == b'J' || *val == b'7') {
+    } else if tile((-1, 0)).is_some_and(| val | val == b'-' || val == b'L' || val == b'F') {
 
-        start_direction = b'E';
+        start_direction = b'W';
 
-    } else if graph
-        .get(&(start_x, start_y + 1))
-        .is_some_and(| val | *val == b'|' || *val == b'L' || *val == b'J') {
+    } else if tile((0, 1)).is_some_and(| val | val == b'|' || val == b'L' || val == b'J') {
 
         start_direction = b'S';
 
-    } else if graph
-        .get(&(start_x + 1, start_y))
-        .is_some_and(| val | *val == b'-' || *val == b'L' || *val == b'F') {
+    } else if tile((1, 0)).is_some_and(| val | val == b'-' || val == b'J' || val == b'7') {
 
-        start_direction = b'W';
+        start_direction = b'E';
     }
 
     start_direction
@@ -99,24 +192,108 @@ fn get_start_direction(
 
 
 #[inline]
-fn get_new_direction(
-    graph: &HashMap<(isize, isize), u8>,
-    position: &(isize, isize), previous_direction: u8
-) -> u8 {
+fn get_new_direction(grid: &Grid<u8>, position: Position, previous_direction: u8) -> Option<u8> {
     // Ugly way to find the next direction based on the previous direction and the pipe piece.
-    match (previous_direction, graph.get(position).unwrap()) {
-        (b'N', b'|') => b'N',
-        (b'N', b'F') => b'E',
-        (b'N', b'7') => b'W',
-        (b'E', b'-') => b'E',
-        (b'E', b'J') => b'N',
-        (b'E', b'7') => b'S',
-        (b'S', b'|') => b'S',
-        (b'S', b'J') => b'W',
-        (b'S', b'L') => b'E',
-        (b'W', b'-') => b'W',
-        (b'W', b'L') => b'N',
-        (b'W', b'F') => b'S',
-        _ => unreachable!()
+    // None if the pipe piece does not connect to where we came from.
+    match (previous_direction, grid[position]) {
+        (b'N', b'|') => Some(b'N'),
+        (b'N', b'F') => Some(b'E'),
+        (b'N', b'7') => Some(b'W'),
+        (b'E', b'-') => Some(b'E'),
+        (b'E', b'J') => Some(b'N'),
+        (b'E', b'7') => Some(b'S'),
+        (b'S', b'|') => Some(b'S'),
+        (b'S', b'J') => Some(b'W'),
+        (b'S', b'L') => Some(b'E'),
+        (b'W', b'-') => Some(b'W'),
+        (b'W', b'L') => Some(b'N'),
+        (b'W', b'F') => Some(b'S'),
+        _ => None
     }
 }
+
+
+/// Generate a square grid with a loop of pipes through the start tile, among pipes that are not part of the loop.
+/// The size is the width and height of the grid, at least five.
+/// Only the two pipes of the loop connect to the start tile.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+    let columns = size - 2;
+
+    // Column x of the loop spans the rows top[x] to bottom[x], with a border of one tile around the loop.
+    // The bottom is below the tops of the neighbouring columns so that the loop never crosses itself,
+    // and the outer columns are straight so that the loop never doubles back on itself.
+    let mut top: Vec<usize> = (0..columns).map(| _ | rng.range(1..size / 2)).collect();
+    top[columns - 1] = top[columns - 2];
+    let mut bottom: Vec<usize> = (0..columns)
+        .map(| x | {
+            let lowest_top = top[x.saturating_sub(2)..(x + 3).min(columns)].iter().max().unwrap();
+            (lowest_top + rng.range(1..size / 2)).min(size - 2)
+        })
+        .collect();
+    let bottom_left = bottom[0].max(bottom[1]);
+    bottom[..2].fill(bottom_left);
+
+    // Along the top from left to right, down the right side, back along the bottom and up the left side.
+    let mut path: Vec<Position> = vec![(1, top[0])];
+    let mut walk = | from: usize, to: usize, horizontal: bool | {
+        for _ in 0..from.abs_diff(to).max(horizontal as usize) {
+            let (x, y) = path[path.len() - 1];
+            path.push(match (horizontal, from < to) {
+                (true, true) => (x + 1, y),
+                (true, false) => (x - 1, y),
+                (false, true) => (x, y + 1),
+                (false, false) => (x, y - 1)
+            });
+        }
+    };
+    for x in 1..columns {
+        walk(0, 1, true);
+        walk(top[x - 1], top[x], false);
+    }
+    walk(top[columns - 1], bottom[columns - 1], false);
+    for x in (1..columns).rev() {
+        walk(1, 0, true);
+        walk(bottom[x], bottom[x - 1], false);
+    }
+    walk(bottom[0], top[0], false);
+    path.pop();  // Back at the start.
+
+    let mut tiles: Vec<Vec<u8>> = (0..size)
+        .map(| _ | (0..size).map(| _ | if rng.chance(0.3) { b'.' } else { rng.pick_char("|-LJ7F") as u8 }).collect())
+        .collect();
+    let mut on_loop: Vec<Vec<bool>> = vec![vec![false; size]; size];
+
+    let direction = | from: Position, to: Position | match (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize) {
+        (0, -1) => 'N',
+        (1, 0) => 'E',
+        (0, 1) => 'S',
+        _ => 'W'
+    };
+    for (index, &(x, y)) in path.iter().enumerate() {
+        let previous = path[(index + path.len() - 1) % path.len()];
+        let next = path[(index + 1) % path.len()];
+
+        let mut directions = [direction((x, y), previous), direction((x, y), next)];
+        directions.sort();
+        tiles[y][x] = match directions {
+            ['N', 'S'] => b'|',
+            ['E', 'W'] => b'-',
+            ['E', 'N'] => b'L',
+            ['N', 'W'] => b'J',
+            ['S', 'W'] => b'7',
+            _ => b'F'
+        };
+        on_loop[y][x] = true;
+    }
+
+    let (x, y) = *rng.pick(&path);
+    tiles[y][x] = b'S';
+    for (x, y) in [(x, y - 1), (x + 1, y), (x, y + 1), (x - 1, y)] {
+        if !on_loop[y][x] {
+            tiles[y][x] = b'.';
+        }
+    }
+
+    tiles.into_iter().map(| row | String::from_utf8(row).unwrap() + "\n").collect()
+}
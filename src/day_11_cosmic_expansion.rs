@@ -1,43 +1,88 @@
 use std::collections::HashSet;
+#[cfg(feature = "fs")]
 use std::fs;
 
-const EXPANSION_PART_ONE: usize = 1;
-const EXPANSION_PART_TWO: usize = 999_999;
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util;
+use crate::util::random::Rng;
 
+const EXPANSION_PART_ONE: u64 = 1;
+const EXPANSION_PART_TWO: u64 = 999_999;
 
-pub fn determine_sum_of_distances(file_path: &str) -> (usize, usize) {
-    let data  = fs::read_to_string(file_path).unwrap();
 
-    // Parse the data and extract the galaxies
+pub struct CosmicExpansion;
+
+impl Solver for CosmicExpansion {
+    fn day(&self) -> u8 { 11 }
+
+    fn title(&self) -> &'static str { "Cosmic Expansion" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Sum of Distances 1", "Sum of Distances 2"] }
+
+    fn input_file(&self) -> &'static str { "day_11_cosmic_expansion.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_sum_of_distances(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let galaxies = parse(data)?;
+    Ok((part_one(&galaxies)?, part_two(&galaxies)?))
+}
+
+/// Parse the data and extract the (x, y) positions of the galaxies
+pub fn parse(data: &str) -> Result<Vec<(usize, usize)>> {
     let mut galaxies: Vec<(usize, usize)> = Vec::new();
-    for (y, line) in data.lines().enumerate() {
-        galaxies.extend(line
+    for line in util::lines(data) {
+        if let Some(column) = line.text.find(| c | c != '.' && c != '#') {
+            return Err(line.unknown_symbol(&line.text[column..]));
+        }
+
+        galaxies.extend(line.text
             .match_indices('#')
-            .map(| item | (item.0, y))
+            .map(| item | (item.0, line.index))
         );
     }
+    Ok(galaxies)
+}
+
+pub fn part_one(galaxies: &[(usize, usize)]) -> Result<u64> {
+    Ok(expanded_distance(galaxies, EXPANSION_PART_ONE))
+}
 
+pub fn part_two(galaxies: &[(usize, usize)]) -> Result<u64> {
+    Ok(expanded_distance(galaxies, EXPANSION_PART_TWO))
+}
+
+
+fn expanded_distance(galaxies: &[(usize, usize)], expansion_coefficient: u64) -> u64 {
     // Unzip X and Y coordinates of galaxies, determine X/Y max
     let (gal_x, gal_y): (Vec<usize>, Vec<usize>) = galaxies.iter().cloned().unzip();
     let expansion_x: Vec<usize> = generate_expansion_vec(gal_x.clone());
     let expansion_y: Vec<usize> = generate_expansion_vec(gal_y.clone());
 
     // Expand the galaxies and calculate the distance
-    let gal_x_expanded: Vec<usize> = expand_galaxy(gal_x.clone(), &expansion_x, EXPANSION_PART_ONE);
-    let gal_y_expanded: Vec<usize> = expand_galaxy(gal_y.clone(), &expansion_y, EXPANSION_PART_ONE);
-    let distance_part_one: usize = manhattan_distance(gal_x_expanded, gal_y_expanded);
-
-    let gal_x_expanded: Vec<usize> = expand_galaxy(gal_x, &expansion_x, EXPANSION_PART_TWO);
-    let gal_y_expanded: Vec<usize> = expand_galaxy(gal_y, &expansion_y, EXPANSION_PART_TWO);
-    let distance_part_two: usize = manhattan_distance(gal_x_expanded, gal_y_expanded);
-
-    (distance_part_one, distance_part_two)
+    let gal_x_expanded: Vec<u64> = expand_galaxy(gal_x, &expansion_x, expansion_coefficient);
+    let gal_y_expanded: Vec<u64> = expand_galaxy(gal_y, &expansion_y, expansion_coefficient);
+    manhattan_distance(gal_x_expanded, gal_y_expanded)
 }
 
 
 fn generate_expansion_vec(occupied_indices: Vec<usize>) -> Vec<usize> {
     // Generate the vector for expanding the galaxy based on where the empty indices are.
-    let max_index = *occupied_indices.iter().max().unwrap();
+    let max_index = occupied_indices.iter().max().copied().unwrap_or(0);
 
     let empty_indices: HashSet<usize> = (0..max_index)
         .collect::<HashSet<usize>>()
@@ -57,31 +102,63 @@ fn generate_expansion_vec(occupied_indices: Vec<usize>) -> Vec<usize> {
 }
 
 
-fn expand_galaxy(mut galaxies: Vec<usize>, expansion: &[usize],  expansion_coefficient: usize) -> Vec<usize>{
+fn expand_galaxy(galaxies: Vec<usize>, expansion: &[usize],  expansion_coefficient: u64) -> Vec<u64>{
     // Expand the galaxy by the expansion coefficient.
-    for gal in galaxies.iter_mut() {
-        *gal += expansion[*gal] * expansion_coefficient;
-    }
     galaxies
+        .into_iter()
+        .map(| gal | gal as u64 + expansion[gal] as u64 * expansion_coefficient)
+        .collect()
 }
 
 
-fn manhattan_distance(x_values: Vec<usize>, y_values: Vec<usize>) -> usize {
+fn manhattan_distance(x_values: Vec<u64>, y_values: Vec<u64>) -> u64 {
     // Efficient approach to determine the manhattan distance between all combinations of points.
     manhattan_distance_single_axis(x_values) + manhattan_distance_single_axis(y_values)
 }
 
 
 #[inline]
-fn manhattan_distance_single_axis(mut values: Vec<usize>) -> usize {
+fn manhattan_distance_single_axis(mut values: Vec<u64>) -> u64 {
     values.sort();
 
-    let mut combined_distance: usize = 0;
-    let mut cumulative_sum: usize = 0;
+    let mut combined_distance: u64 = 0;
+    let mut cumulative_sum: u64 = 0;
     for (i, item) in values.iter().enumerate() {
-        combined_distance += item * i - cumulative_sum;
+        combined_distance += item * i as u64 - cumulative_sum;
         cumulative_sum += item;
     }
 
     combined_distance
-}
\ No newline at end of file
+}
+
+
+/// Generate a square image of sparse galaxies, with about a tenth of the rows and columns empty.
+/// The size is the width and height of the image.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let empty_rows: Vec<bool> = (0..size).map(| _ | rng.chance(0.1)).collect();
+    let empty_columns: Vec<bool> = (0..size).map(| _ | rng.chance(0.1)).collect();
+
+    let mut data = String::new();
+    for empty_row in empty_rows {
+        for &empty_column in &empty_columns {
+            data.push(if !empty_row && !empty_column && rng.chance(0.02) { '#' } else { '.' });
+        }
+        data.push('\n');
+    }
+    data
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expanded_distance() {
+        let galaxies = parse(include_str!("../data/example/day_11_cosmic_expansion.txt")).unwrap();
+
+        // Each empty row or column is replaced by 10 and by 100 empty rows or columns.
+        assert_eq!(expanded_distance(&galaxies, 9), 1030);
+        assert_eq!(expanded_distance(&galaxies, 99), 8410);
+    }
+}
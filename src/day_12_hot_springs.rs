@@ -1,97 +1,179 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
-use cached::{Cached, UnboundCache};
+use crate::error::{Error, Result};
+use crate::observe;
+use crate::solver::{self, Solution, Solver};
+use crate::util;
+use crate::util::random::Rng;
 
 
-pub fn determine_sum_of_arrangements(file_path: &str) -> (usize, usize) {
-    let data  = fs::read_to_string(file_path).unwrap();
+pub struct HotSprings;
 
-    let mut total_arrangements_part_one: usize = 0;
-    let mut total_arrangements_part_two: usize = 0;
+impl Solver for HotSprings {
+    fn day(&self) -> u8 { 12 }
 
-    for line in data.lines() {
-        let (records, groups) = line.split_once(' ').unwrap();
+    fn title(&self) -> &'static str { "Hot Springs" }
 
-        let groups: Vec<usize> = groups
-            .split(',')
-            .map(| c | c.parse::<usize>().unwrap())
-            .collect();
+    fn labels(&self) -> [&'static str; 2] { ["Total Arrangements 1", "Total Arrangements 2"] }
 
-        let mut cache: UnboundCache<(usize, usize, usize), usize> = UnboundCache::new();
-        
-        total_arrangements_part_one += arrangements(
-            &mut cache, records.as_bytes(), None, &groups
-        );
-        
-        total_arrangements_part_two += arrangements(
-            &mut cache, [records].repeat(5).join("?").as_bytes(), None, &groups.repeat(5)
-        );
+    fn input_file(&self) -> &'static str { "day_12_hot_springs.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
     }
 
-    (total_arrangements_part_one, total_arrangements_part_two)
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
 }
 
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_sum_of_arrangements(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
 
-fn arrangements(
-    cache: &mut UnboundCache<(usize, usize, usize), usize>,
-    records: &[u8], within_a_group: Option<usize>, groups: &[usize]
-) -> usize {
-    if records.is_empty() {  // Base-case we went over all records.
-        return match (groups.len(), within_a_group) {
-            // At the end, no groups left => Solution found.
-            (0, None) => 1,
-
-            // At the end, last group fits => Solution found.
-            (1, Some(group)) if group == groups[0] => 1,
-
-            // At the end but unplaced or non-fitting group => No solution.
-            _ => 0,
-        };
-    }
-
-    // In a group but no groups left  => No solution.
-    if within_a_group.is_some() && groups.is_empty() {
-        return 0;
-    }
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let rows = parse(data)?;
+    Ok((part_one(&rows)?, part_two(&rows)?))
+}
 
-    let cache_key = (records.len(), within_a_group.unwrap_or(0), groups.len());
-    if let Some(&count) = cache.cache_get(&cache_key) {
-        return count;
-    }
+/// The condition records of a row of springs and the sizes of the damaged groups.
+pub type Row<'a> = (&'a str, Vec<usize>);
+
+pub fn parse(data: &str) -> Result<Vec<Row<'_>>> {
+    util::lines(data)
+        .map(| line | {
+            let (records, groups) = line.split_once(line.text, " ")?;
+
+            if let Some(column) = records.find(| c | !".#?".contains(c)) {
+                return Err(line.unknown_symbol(&records[column..]));
+            }
+
+            let groups: Vec<usize> = groups
+                .split(',')
+                .map(| c | match line.parse::<usize>(c)? {
+                    0 => Err(line.error(c, "a group has at least one damaged spring")),
+                    group => Ok(group)
+                })
+                .collect::<Result<_>>()?;
+
+            Ok((records, groups))
+        })
+        .collect()
+}
 
-    // Check the first character and decide what to do next.
-    let count: usize = match(records[0], within_a_group) {
-        // Not in a group and the current character is not a group => Just continue.
-        (b'.', None) => arrangements(cache, &records[1..], None, groups),
+pub fn part_one(rows: &[Row]) -> Result<u64> {
+    rows.iter().try_fold(0, | total: u64, (records, groups) | {
+        count_arrangements(records.as_bytes(), groups)
+            .and_then(| count | total.checked_add(count))
+            .ok_or_else(too_many)
+    })
+}
 
-        // The next group can't be placed => No solution.
-        (b'.', Some(group)) if group != groups[0] => 0,
+/// Unfold each row five times.
+pub fn part_two(rows: &[Row]) -> Result<u64> {
+    rows.iter().enumerate().try_fold(0, | total: u64, (index, (records, groups)) | {
+        observe::progress("unfolded rows", index + 1, Some(rows.len()));
+        count_arrangements([*records].repeat(5).join("?").as_bytes(), &groups.repeat(5))
+            .and_then(| count | total.checked_add(count))
+            .ok_or_else(too_many)
+    })
+}
 
-        // Next group fits => Continue to next group (_group == group[0] if we did not match previous clause).
-        (b'.', Some(_group)) => arrangements(cache, &records[1..], None, &groups[1..]),
 
-        // Currently not in a group => Start a new group and continue.
-        (b'#', None) => arrangements(cache, &records[1..], Some(1), groups),
+fn too_many() -> Error {
+    Error::unsolvable("the arrangements do not fit a u64")
+}
 
-        // Still in a group => Increment within_a_group and continue (Some(x).map(| x | x + 1) == Some(x + 1)).
-        (b'#', Some(_)) => arrangements(cache, &records[1..], within_a_group.map(|x | x + 1), groups),
 
-        // Still in a group, but it can't be placed yet => Increment and continue.
-        (b'?', Some(group)) if group != groups[0] => {
-            arrangements(cache, &records[1..], within_a_group.map(|x| x + 1), groups)
+/// Generate a thousand rows of condition records with runs of unknown springs.
+/// The size is the length of the records, longer records have larger groups and longer runs of unknown springs.
+/// Every row has at least one arrangement, and the sum of the arrangements of the unfolded rows fits the answer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let length = size.max(1);
+    let mut total: u64 = 0;
+    let mut data = String::new();
+
+    for _ in 0..1000 {
+        // Place the damaged groups with at least one operational spring between them.
+        let mut springs: Vec<u8> = Vec::new();
+        let mut groups: Vec<usize> = Vec::new();
+        while springs.len() < length {
+            let mut gap = rng.range(usize::from(!springs.is_empty())..4);
+            let mut group = rng.range(1..(length / 8).max(3) + 1);
+            if springs.len() + gap + group > length {
+                if !groups.is_empty() {
+                    break;
+                }
+                // Every row has at least one group.
+                (gap, group) = (0, group.min(length));
+            }
+            springs.extend(std::iter::repeat_n(b'.', gap).chain(std::iter::repeat_n(b'#', group)));
+            groups.push(group);
+        }
+        springs.resize(length, b'.');
+
+        // Hide the condition of the springs, less of them if there are too many arrangements.
+        let mut hidden: f64 = 0.12;
+        loop {
+            let mut records = springs.clone();
+            let mut position: usize = 0;
+            while position < length {
+                if rng.chance(hidden) {
+                    let run = rng.range(1..length / 4 + 2).min(length - position);
+                    records[position..position + run].fill(b'?');
+                    position += run;
+                }
+                position += 1;
+            }
+
+            let unfolded = [records.as_slice()].repeat(5).join(&b'?');
+            let count = count_arrangements(&unfolded, &groups.repeat(5));
+            if let Some(sum) = count.and_then(| count | total.checked_add(count)) {
+                total = sum;
+                let groups: Vec<String> = groups.iter().map(usize::to_string).collect();
+                data += &format!("{} {}\n", String::from_utf8(records).unwrap(), groups.join(","));
+                break;
+            }
+            hidden /= 2.;
         }
+    }
+    data
+}
 
-        // Next group fits => Continue to next group (because of previous claus).
-        (b'?', Some(_)) => arrangements(cache, &records[1..], None, &groups[1..]),
 
-        // We are maybe in a group => Consider both possibilities.
-        (b'?', None) => {
-            arrangements(cache, &records[1..], Some(1), groups) + arrangements(cache, &records[1..], None, groups)
-        },
+/// Count the arrangements of the groups in the records, `None` if there are more than fit a u64.
+/// The count goes bottom-up, one group at a time from the last, so that a long row needs no recursion
+/// and only two counts per record.
+fn count_arrangements(records: &[u8], groups: &[usize]) -> Option<u64> {
+    let n = records.len();
 
-        _ => unreachable!(),
-    };
+    // The number of springs from each position up to the next operational spring.
+    let mut run: Vec<usize> = vec![0; n + 1];
+    for i in (0..n).rev() {
+        run[i] = if records[i] == b'.' { 0 } else { run[i + 1] + 1 };
+    }
 
-    cache.cache_set(cache_key, count);
-    count
-}
\ No newline at end of file
+    // ways[i] is the number of arrangements of the groups after the current one in records[i..],
+    // where position n + 1 is past the operational spring that ends a group at the end of the records.
+    // Without any groups left, only records without damaged springs have an arrangement.
+    let mut ways: Vec<u64> = vec![1; n + 2];
+    for i in (0..n).rev() {
+        ways[i] = if records[i] == b'#' { 0 } else { ways[i + 1] };
+    }
+
+    for &group in groups.iter().rev() {
+        let mut with_group: Vec<u64> = vec![0; n + 2];
+        for i in (0..n).rev() {
+            let mut count = if records[i] != b'#' { with_group[i + 1] } else { 0 };
+            if records[i] != b'.' && run[i] >= group && records.get(i + group) != Some(&b'#') {
+                count = count.checked_add(ways[i + group + 1])?;
+            }
+            with_group[i] = count;
+        }
+        ways = with_group;
+    }
+    Some(ways[0])
+}
@@ -1,64 +1,125 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-pub fn determine_sum_of_notes(file_path: &str) -> (usize, usize) {
-    // Determine the sum of notes by finding the palindrome in each pattern.
-    let data  = fs::read_to_string(file_path)
-        .unwrap()
-        .replace('.', "0")
-        .replace('#', "1");
 
-    let mut sum_of_notes: usize = 0;
-    let mut sum_of_notes_with_smudge: usize = 0;
+pub struct PointOfIncidence;
 
-    for pattern in data.split("\n\n") {
-        let (line, column) = encode_pattern(pattern);
+impl Solver for PointOfIncidence {
+    fn day(&self) -> u8 { 13 }
 
-        // For part 2 we want to find a new solution when using a smudge.
-        // To achieve this we provide the previous start_end so that we can exclude that solution.
-        let (line_count, start_end) = find_palindrome(&line, (0, 0), 0);
-        let (line_smudge, _) = find_palindrome(&line, start_end, 1);
+    fn title(&self) -> &'static str { "Point of Incidence" }
 
-        let (col_count, start_end) = find_palindrome(&column, (0, 0), 0);
-        let (col_smudge, _) = find_palindrome(&column, start_end, 1);
+    fn labels(&self) -> [&'static str; 2] { ["Sum of Notes", "Sum of Notes with Smudge"] }
 
-        sum_of_notes += line_count * 100 + col_count;
-        sum_of_notes_with_smudge += line_smudge * 100 + col_smudge;
+    fn input_file(&self) -> &'static str { "day_13_point_of_incidence.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
-    (sum_of_notes, sum_of_notes_with_smudge)
 }
 
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_sum_of_notes(file_path: &str) -> Result<(usize, usize)> {
+    solve(&fs::read_to_string(file_path)?)
+}
 
-#[inline]
-fn encode_pattern(pattern: &str) -> (Vec<u32>, Vec<u32>) {
-    // Turn the pattern of 0s and 1s into u32 bitfields.
-    // For generating the columns, some awkward loops are used
-    // These push the characters to the correct column strings.
+pub fn solve(data: &str) -> Result<(usize, usize)> {
+    let patterns = parse(data)?;
+    Ok((part_one(&patterns)?, part_two(&patterns)?))
+}
+
+/// A pattern encoded as bitfields of its lines and of its columns.
+pub type Pattern = (Vec<u32>, Vec<u32>);
+
+pub fn parse(data: &str) -> Result<Vec<Pattern>> {
+    // Patterns are separated by empty lines.
+    let lines: Vec<Line> = util::lines(data).collect();
+
+    lines
+        .split(| line | line.text.is_empty())
+        .filter(| pattern | !pattern.is_empty())
+        .map(encode_pattern)
+        .collect()
+}
+
+/// Determine the sum of notes by finding the palindrome in each pattern.
+pub fn part_one(patterns: &[Pattern]) -> Result<usize> {
+    Ok(patterns
+        .iter()
+        .map(| (line, column) | {
+            let (line_count, _) = find_palindrome(line, (0, 0), 0);
+            let (col_count, _) = find_palindrome(column, (0, 0), 0);
+            line_count * 100 + col_count
+        })
+        .sum())
+}
+
+/// For part 2 we want to find a new solution when using a smudge.
+/// To achieve this we provide the previous start_end so that we can exclude that solution.
+pub fn part_two(patterns: &[Pattern]) -> Result<usize> {
+    Ok(patterns
+        .iter()
+        .map(| (line, column) | {
+            let (_, start_end) = find_palindrome(line, (0, 0), 0);
+            let (line_smudge, _) = find_palindrome(line, start_end, 1);
+
+            let (_, start_end) = find_palindrome(column, (0, 0), 0);
+            let (col_smudge, _) = find_palindrome(column, start_end, 1);
 
-    // First newline indicates the length of a row -> number of columns.
-    let mut columns: Vec<String> = vec![String::new(); pattern.find('\n').unwrap()];
+            line_smudge * 100 + col_smudge
+        })
+        .sum())
+}
+
+
+#[inline]
+fn encode_pattern(pattern: &[Line]) -> Result<Pattern> {
+    // Turn the pattern of '.' and '#' into u32 bitfields, where '#' is a 1.
+    // The bits of each line are also shifted into the bitfield of their column.
+
+    // First line indicates the length of a row -> number of columns.
+    let width: usize = pattern[0].text.len();
+    if width > 32 || pattern.len() > 32 {
+        return Err(pattern[0].error(pattern[0].text, "a pattern can be at most 32 by 32 tiles"));
+    }
 
     let mut line_code: Vec<u32> = Vec::new();
-    for line in pattern.lines() {
+    let mut column_code: Vec<u32> = vec![0; width];
 
-        for (i, char) in line.chars().enumerate() {
-            columns[i].push(char);
+    for line in pattern {
+        if line.text.len() != width {
+            return Err(line.error(line.text, format!("expected a row of {width} tiles")));
         }
 
-        line_code.push(u32::from_str_radix(line, 2).unwrap());
+        let mut code: u32 = 0;
+        for (i, char) in line.text.char_indices() {
+            let bit: u32 = match char {
+                '.' => 0,
+                '#' => 1,
+                _ => return Err(line.unknown_symbol(&line.text[i..]))
+            };
+            code = code << 1 | bit;
+            column_code[i] = column_code[i] << 1 | bit;
+        }
+        line_code.push(code);
     }
 
-    let column_code: Vec<u32> = columns
-        .iter()
-        .map(| str | u32::from_str_radix(str, 2).unwrap())
-        .collect();
-
-    (line_code, column_code)
+    Ok((line_code, column_code))
 }
 
 
 #[inline]
-fn find_palindrome(pattern: &Vec<u32>, previous: (usize, usize), smudges: u32) -> (usize, (usize, usize)) {
+fn find_palindrome(pattern: &[u32], previous: (usize, usize), smudges: u32) -> (usize, (usize, usize)) {
     // Loop over all indices and try to expand a palindrome from there.
     // We stop at the first answer that satisfies all conditions.
     for index in 0..pattern.len() {
@@ -107,3 +168,73 @@ fn expand_from_center(
     }
     (start, end)
 }
+
+
+/// Generate patterns of five to seventeen rows and columns.
+/// The size is the number of patterns.
+/// Each pattern has a single reflection, and a single smudge that makes a different line a reflection.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut patterns: Vec<String> = Vec::new();
+
+    while patterns.len() < size {
+        // Mirror a random pattern in a vertical line and in a horizontal line.
+        // The vertical line does not reach the outer columns on one side, a tile there is the smudge
+        // that breaks the horizontal reflection, without breaking the vertical one.
+        let (width, height) = (rng.range(5..18), rng.range(5..18));
+        let (vertical, horizontal) = (rng.range(1..width), rng.range(1..height));
+        if 2 * vertical == width {
+            continue;
+        }
+
+        let mut tiles: Vec<Vec<bool>> = (0..height).map(| _ | (0..width).map(| _ | rng.chance(0.5)).collect()).collect();
+        for row in tiles.iter_mut() {
+            for x in vertical..width.min(2 * vertical) {
+                row[x] = row[2 * vertical - 1 - x];
+            }
+        }
+        for y in horizontal..height.min(2 * horizontal) {
+            tiles[y] = tiles[2 * horizontal - 1 - y].clone();
+        }
+
+        let x = match 2 * vertical < width {
+            true => rng.range(2 * vertical..width),
+            false => rng.range(0..2 * vertical - width)
+        };
+        let reach = horizontal.min(height - horizontal);
+        let y = rng.range(horizontal - reach..horizontal + reach);
+        tiles[y][x] = !tiles[y][x];
+
+        if rng.chance(0.5) {
+            tiles = transpose(&tiles);
+        }
+
+        // Reject the pattern if any other line happens to be a reflection, with or without a smudge.
+        let mut mismatches: Vec<usize> = reflection_mismatches(&tiles);
+        mismatches.extend(reflection_mismatches(&transpose(&tiles)));
+        mismatches.sort();
+        if mismatches[0] == 0 && mismatches[1] == 1 && mismatches[2] >= 2 {
+            patterns.push(tiles
+                .iter()
+                .map(| row | row.iter().map(| &rock | if rock { '#' } else { '.' }).collect::<String>() + "\n")
+                .collect());
+        }
+    }
+    patterns.join("\n")
+}
+
+
+/// The number of tiles that differ from their reflection, for each horizontal line between the rows.
+fn reflection_mismatches(tiles: &[Vec<bool>]) -> Vec<usize> {
+    (1..tiles.len())
+        .map(| line | {
+            (0..line.min(tiles.len() - line))
+                .map(| i | tiles[line - 1 - i].iter().zip(&tiles[line + i]).filter(| (a, b) | a != b).count())
+                .sum()
+        })
+        .collect()
+}
+
+
+fn transpose(tiles: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    (0..tiles[0].len()).map(| x | tiles.iter().map(| row | row[x]).collect()).collect()
+}
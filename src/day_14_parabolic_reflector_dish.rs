@@ -1,92 +1,203 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+#[cfg(feature = "fs")]
 use std::fs;
+use std::hash::{BuildHasher, RandomState};
 use std::str::FromStr;
-use std::string::ParseError;
 
-pub fn determine_total_load(file_path: &str) -> (usize, usize) {
-    let data: String = fs::read_to_string(file_path)
-        .unwrap()
-        .replace('#', "0")
-        .replace('O', "1")
-        .replace('.', "2");
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+use crate::observe;
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Frames, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::cycle::Cycle;
+use crate::util::grid::Grid;
+use crate::util::random::Rng;
 
-    // Part One
-    let mut dish: ReflectorDish = ReflectorDish::from_str(&data).unwrap();
+pub struct ParabolicReflectorDish;
+
+impl Solver for ParabolicReflectorDish {
+    fn day(&self) -> u8 { 14 }
+
+    fn title(&self) -> &'static str { "Parabolic Reflector Dish" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Total Load 1", "Total Load 2"] }
+
+    fn input_file(&self) -> &'static str { "day_14_parabolic_reflector_dish.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn render(&self, data: &str, part: Part) -> Option<Result<Canvas>> {
+        Some(render(data, part))
+    }
+
+    #[cfg(feature = "render")]
+    fn animate(&self, data: &str, part: Part, frames: Frames) -> Option<Result<Vec<Canvas>>> {
+        Some(animate(data, part, frames))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_total_load(file_path: &str) -> Result<(usize, usize)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, usize)> {
+    let dish = parse(data)?;
+    Ok((part_one(&dish)?, part_two(&dish)?))
+}
+
+pub fn parse(data: &str) -> Result<ReflectorDish> {
+    ReflectorDish::from_str(data)
+}
+
+pub fn part_one(dish: &ReflectorDish) -> Result<usize> {
+    Ok(tilted_north(dish).get_beam_load())
+}
+
+/// The spin cycles soon start to repeat, so find the cycle and take the beam load of the dish at the target.
+pub fn part_two(dish: &ReflectorDish) -> Result<usize> {
+    Ok(spun(dish).get_beam_load())
+}
+
+/// Draw the rounded rocks (O) after tilting the dish north, or after the spin cycles of part two.
+#[cfg(feature = "render")]
+pub fn render(data: &str, part: Part) -> Result<Canvas> {
+    let dish = parse(data)?;
+    let mut dish = match part {
+        Part::Two => spun(&dish),
+        _ => tilted_north(&dish)
+    };
+    let load = dish.get_beam_load();
+
+    // Turn the dish back so that north is up.
+    dish.rotate_clockwise();
+    let rocks = dish.dish.iter().filter(| (_, &tile) | tile == 1).count();
+    Ok(draw(&dish.dish, format!("O: {rocks} rounded rocks with a total load of {load}.")))
+}
+
+/// Draw the dish before and after each tilt, the tilt to the north of part one or the tilts of the spin cycles.
+/// Step `n` is the dish after `n` tilts, part one has only one.
+#[cfg(feature = "render")]
+pub fn animate(data: &str, part: Part, frames: Frames) -> Result<Vec<Canvas>> {
+    const DIRECTIONS: [&str; 4] = ["north", "west", "south", "east"];
+
+    let mut dish = parse(data)?;
     dish.rotate_anti_clockwise();
-    dish.tilt();
-    let total_load_part_one = dish.get_beam_load();
+    let last_step = match part {
+        Part::Two => frames.last,
+        _ => frames.last.min(1)
+    };
+
+    // The tilts roll the rocks to the start of the rows, so the dish turns a quarter before each tilt of a spin cycle.
+    // The dish starts with three clockwise quarter turns, such that the first tilt is to the north.
+    let mut canvases: Vec<Canvas> = Vec::new();
+    for step in 0..=last_step {
+        if step > 1 {
+            dish.rotate_clockwise();
+        }
+        if step > 0 {
+            dish.tilt();
+        }
+        if !frames.contains(step) {
+            continue;
+        }
+
+        let mut north_up = dish.dish.clone();
+        for _ in 0..(1 + 4 - step.saturating_sub(1) % 4) % 4 {
+            north_up = north_up.rotate_clockwise();
+        }
+        let load: usize = north_up
+            .iter()
+            .filter(| (_, &tile) | tile == 1)
+            .map(| ((_, y), _) | north_up.height() - y)
+            .sum();
+        let caption = match step {
+            0 => format!("Step 0: the total load is {load}."),
+            _ => format!("Step {step}: tilted {}, the total load is {load}.", DIRECTIONS[(step - 1) % 4])
+        };
+        canvases.push(draw(&north_up, caption));
+    }
+    Ok(canvases)
+}
+
 
-    // Part Two
-    // To find the cycle keep a history of dishes
-    // Once we find a repeating cycle in our history, align with the target number and take the beam load.
-    let mut dish: ReflectorDish = ReflectorDish::from_str(&data).unwrap();
-    let mut total_load_part_two: usize = 0;
-    let mut dish_history: HashMap<ReflectorDish, usize> = HashMap::new();
+/// Draw a dish with north up.
+#[cfg(feature = "render")]
+fn draw(dish: &Grid<u8>, caption: String) -> Canvas {
+    let tiles = dish.map(| &tile | match tile {
+        0 => Tile::new('#', Style::Plain),
+        1 => Tile::new('O', Style::Primary),
+        _ => Tile::new('.', Style::Faint)
+    });
+    Canvas::new(tiles, caption)
+}
+
+
+/// The dish tilted north, rotated such that north is at the start of the rows.
+fn tilted_north(dish: &ReflectorDish) -> ReflectorDish {
+    let mut dish: ReflectorDish = dish.clone();
+    dish.rotate_anti_clockwise();
+    dish.tilt();
+    dish
+}
 
+/// The dish after a billion spin cycles, rotated such that north is at the start of the rows.
+fn spun(dish: &ReflectorDish) -> ReflectorDish {
+    let mut dish: ReflectorDish = dish.clone();
     dish.rotate_anti_clockwise(); // Prime the board because the beam load is defined from top to bottom.
-    for i in 0..1_000_000_000 {
-        if let Some(previous_i) = dish_history.insert(dish.clone(), i) {
-            if (1_000_000_000 - i) % (i - previous_i) == 0 {
-                total_load_part_two = dish.get_beam_load();
-                break;
+
+    // Brent's algorithm spins through some of the states more than once, so only count the distinct ones.
+    // Their hashes are enough for the progress, and they are only kept while someone observes the solver.
+    let hasher = RandomState::new();
+    let mut seen: Option<HashSet<u64>> = observe::current().is_observed().then(HashSet::new);
+    let spin = | dish: &ReflectorDish | {
+        let mut dish = dish.clone();
+        dish.spin_cycle();
+        if let Some(seen) = seen.as_mut() {
+            if seen.insert(hasher.hash_one(&dish)) {
+                observe::progress("distinct spin cycle states", seen.len(), None);
             }
         }
-        dish.spin_cycle();
-    }
+        dish
+    };
 
-    (total_load_part_one, total_load_part_two)
+    let mut cycle = Cycle::find(dish, spin);
+    observe::value("spin cycle", format!("repeats every {} spins after {} spins", cycle.length(), cycle.prefix()));
+    cycle.state_at(1_000_000_000)
 }
 
-
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-struct ReflectorDish {
-    dish: Vec<Vec<u8>>
+pub struct ReflectorDish {
+    dish: Grid<u8>
 }
 
 impl ReflectorDish {
     #[inline]
     fn rotate_clockwise(&mut self) {
-        // Rotate clockwise 90 degrees.
-        let len: usize = self.dish[0].len();
-        let mut iters: Vec<_> = self.dish.iter().map(|n| n.iter()).collect();
-
-        let vec: Vec<Vec<u8>> = (0..len)
-            .map(|_| {
-                iters
-                    .iter_mut()
-                    .map(| item| *item.next().unwrap())
-                    .rev()
-                    .collect::<Vec<u8>>()
-            })
-            .collect();
-
-        self.dish = vec;
+        self.dish = self.dish.rotate_clockwise();
     }
 
     #[inline]
     fn rotate_anti_clockwise(&mut self) {
-        let len: usize = self.dish[0].len();
-        let mut iters: Vec<_> = self.dish.iter().map(|n| n.iter()).collect();
-
-        let mut vec: Vec<Vec<u8>> = (0..len)
-            .map(|_| {
-                iters
-                    .iter_mut()
-                    .map(| item| *item.next().unwrap())
-                    .collect::<Vec<u8>>()
-            })
-            .collect();
-        vec.reverse();
-        self.dish = vec;
+        self.dish = self.dish.rotate_anti_clockwise();
     }
 
     #[inline]
     fn tilt(&mut self) {
-        let indices: Vec<Vec<usize>> = self.find_indices(0);
-
-        for (line, idx) in self.dish.iter_mut().zip(indices) {
-            for win in idx.windows(2) {
-                line[win[0]..win[1]].sort();
+        // Roll the rounded rocks to the start of each row, up to the next square rock.
+        for line in self.dish.rows_mut() {
+            for section in line.split_mut(| &tile | tile == 0) {
+                section.sort();
             }
         }
     }
@@ -105,57 +216,52 @@ impl ReflectorDish {
     }
 
     #[inline]
-    fn get_beam_load(&mut self) -> usize {
-        let scoring: Vec<usize> = (0..=self.dish.len()).rev().collect();
+    fn get_beam_load(&self) -> usize {
+        // The load of a rock is its distance to the end of the row.
+        let row_length = self.dish.width();
 
         self.dish
-            .iter()
-            .map(| vec | { vec
+            .rows()
+            .map(| row | { row
                 .iter()
                 .enumerate()
                 .filter(|(_, &val)| val == 1)
-                .map(|(index, _)| scoring[index])
+                .map(|(index, _)| row_length - index)
                 .sum::<usize>()
             })
             .sum()
     }
-
-    #[inline]
-    fn find_indices(&self, find: u8) -> Vec<Vec<usize>> {
-        // Find the indices where vec == find.
-        // Put in between start and end so they may be used as ranges for sorting.
-        let mut indices: Vec<Vec<usize>> = Vec::new();
-
-        for vec in self.dish.iter() {
-            let mut idx = vec![0];
-            idx.extend(vec
-                    .iter()
-                    .enumerate()
-                    .filter(| (_, &val) | val == find)
-                    .map(| (index, _ ) | index)
-            );
-            idx.push(vec.len());
-            indices.push(idx);
-        }
-        indices
-    }
 }
 
 impl FromStr for ReflectorDish {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut dish: Vec<Vec<u8>> = Vec::new();
-        for line in s.lines() {
-            dish.push(
-                line
-                    .chars()
-                    .map(| char | char.to_digit(10).unwrap() as u8)
-                    .collect()
-            );
-        }
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Encode the tiles so that sorting a section moves the rounded rocks to the front.
+        // Square rocks '#' => 0, rounded rocks 'O' => 1, empty space '.' => 2.
+        let dish = Grid::parse(s, | char | match char {
+            '#' => Some(0),
+            'O' => Some(1),
+            '.' => Some(2),
+            _ => None
+        })?;
         Ok(Self { dish })
     }
 }
 
 
+/// Generate a square platform of rounded rocks, square rocks and empty space.
+/// The size is the width and height of the platform.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(| _ | {
+            (0..size)
+                .map(| _ | match rng.range(0..20) {
+                    0..=3 => 'O',
+                    4..=6 => '#',
+                    _ => '.'
+                })
+                .collect::<String>() + "\n"
+        })
+        .collect()
+}
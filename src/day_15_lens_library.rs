@@ -1,30 +1,71 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
 use indexmap::IndexMap;
 
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util::Line;
+use crate::util::random::Rng;
 
-pub fn determine_hash(file_path: &str) -> (usize, usize) {
-    let data = fs::read_to_string(file_path)
-        .unwrap()
-        .replace('\n', "");
 
-    // Part 1
-    let hash_sum: usize = data
+pub struct LensLibrary;
+
+impl Solver for LensLibrary {
+    fn day(&self) -> u8 { 15 }
+
+    fn title(&self) -> &'static str { "Lens Library" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Hash Sum", "Focusing Power"] }
+
+    fn input_file(&self) -> &'static str { "day_15_lens_library.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_hash(file_path: &str) -> Result<(usize, usize)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, usize)> {
+    let sequence = parse(data)?;
+    Ok((part_one(&sequence)?, part_two(&sequence)?))
+}
+
+/// The initialization sequence is a single line once the newlines are removed.
+pub fn parse(data: &str) -> Result<String> {
+    Ok(data.replace('\n', ""))
+}
+
+pub fn part_one(sequence: &str) -> Result<usize> {
+    Ok(sequence
         .split(',')
         .map(| section | section
             .chars()
             .fold(0, hash_character)
-        ).sum();
+        ).sum())
+}
 
-    // Part 2
-    // Put everything in boxes
+/// Put everything in boxes
+pub fn part_two(sequence: &str) -> Result<usize> {
+    let line = Line { index: 0, text: sequence };
     let mut boxes: Vec<IndexMap<&str, usize>> = vec![IndexMap::new(); 256];
-    for section in data.split(',') {
-        let (label, focal_length) = section.split_once(['=', '-']).unwrap();
+    for section in sequence.split(',') {
+        let (label, focal_length) = section
+            .split_once(['=', '-'])
+            .ok_or_else(|| line.error(section, "expected '=' or '-'"))?;
         let box_idx = label.chars().fold(0, hash_character);
 
         match section.contains('=') {
-            true => boxes[box_idx].insert(label, focal_length.parse::<usize>().unwrap()),
+            true => boxes[box_idx].insert(label, line.parse::<usize>(focal_length)?),
             false => boxes[box_idx].shift_remove(label)
         };
     }
@@ -42,7 +83,7 @@ pub fn determine_hash(file_path: &str) -> (usize, usize) {
             )
         );
 
-    (hash_sum, focusing_power)
+    Ok(focusing_power)
 }
 
 
@@ -50,3 +91,23 @@ pub fn determine_hash(file_path: &str) -> (usize, usize) {
 fn hash_character(current_value: usize, character: char) -> usize {
     (current_value + character as usize) * 17 % 256
 }
+
+
+/// Generate an initialization sequence that adds, replaces and removes lenses of a quarter as many labels.
+/// The size is the number of steps.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let labels: Vec<String> = (0..(size / 4).max(1))
+        .map(| _ | (0..rng.range(2..7)).map(| _ | rng.pick_char("abcdefghijklmnopqrstuvwxyz")).collect())
+        .collect();
+
+    let steps: Vec<String> = (0..size)
+        .map(| _ | {
+            let label = rng.pick(&labels);
+            match rng.chance(0.7) {
+                true => format!("{label}={}", rng.range(1..10)),
+                false => format!("{label}-")
+            }
+        })
+        .collect();
+    steps.join(",") + "\n"
+}
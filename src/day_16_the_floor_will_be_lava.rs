@@ -1,87 +1,188 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+#[cfg(feature = "fs")]
 use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 
-use crate::util::text_to_graph;
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::Result;
+use crate::observe;
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::grid::{Grid, Position};
+use crate::util::random::Rng;
+use crate::util::search::Bfs;
 
-pub fn determine_energized_tiles(file_path: &str) -> (usize, usize) {
-    let data = fs::read_to_string(file_path).unwrap();
-    let graph: HashMap<(isize, isize), u8> = text_to_graph(data);
+pub struct TheFloorWillBeLava;
 
-    let energized_tiles_part_one: usize = breadth_first_search(&graph, ((-1, 0), b'E'));
+impl Solver for TheFloorWillBeLava {
+    fn day(&self) -> u8 { 16 }
 
-    let mut energized_tiles_part_two: [usize; 4] = [0; 4];
-    let (x_max, y_max) = *graph.keys().max().unwrap();
+    fn title(&self) -> &'static str { "The Floor will be Lava" }
 
-    energized_tiles_part_two[0] = (0..y_max)
-        .into_par_iter()
-        .map(| y | breadth_first_search(&graph, ((-1, y), b'E')))
-        .max()
-        .unwrap();
+    fn labels(&self) -> [&'static str; 2] { ["Energized Tiles 1", "Energized Tiles 2"] }
 
-    energized_tiles_part_two[1] = (0..y_max)
-        .into_par_iter()
-        .map(| y | breadth_first_search(&graph, ((x_max + 1, y), b'W')))
-        .max()
-        .unwrap();
+    fn input_file(&self) -> &'static str { "day_16_the_floor_will_be_lava.txt" }
 
-    energized_tiles_part_two[2] = (0..x_max)
-        .into_par_iter()
-        .map(| x | breadth_first_search(&graph, ((x, -1), b'S')))
-        .max()
-        .unwrap();
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn render(&self, data: &str, part: Part) -> Option<Result<Canvas>> {
+        Some(render(data, part))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_energized_tiles(file_path: &str) -> Result<(usize, usize)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, usize)> {
+    let graph = parse(data)?;
+    Ok((part_one(&graph)?, part_two(&graph)?))
+}
+
+pub fn parse(data: &str) -> Result<Grid<u8>> {
+    Grid::from_symbols(data, "./\\|-")
+}
 
-    energized_tiles_part_two[3] = (0..x_max)
-        .into_par_iter()
-        .map(| x | breadth_first_search(&graph, ((x, y_max + 1), b'N')))
+/// The beam enters in the top-left corner heading east.
+pub fn part_one(grid: &Grid<u8>) -> Result<usize> {
+    Ok(count_energized(&energized_tiles(grid, ((0, 0), b'E'))))
+}
+
+/// The beam can enter from any edge tile, find the entry that energizes the most tiles.
+pub fn part_two(grid: &Grid<u8>) -> Result<usize> {
+    let entries = edge_entries(grid);
+
+    // The beams may be followed on the rayon threads, so take the observer along.
+    let scope = observe::current();
+    let n_entries = entries.len();
+    let done = AtomicUsize::new(0);
+
+    #[cfg(feature = "parallel")]
+    let entries = entries.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let entries = entries.into_iter();
+
+    Ok(entries
+        .map(| entry | {
+            let energized = count_energized(&energized_tiles(grid, entry));
+            scope.progress("edge tiles", done.fetch_add(1, Ordering::Relaxed) + 1, Some(n_entries));
+            energized
+        })
         .max()
-        .unwrap();
+        .unwrap_or_default())
+}
+
 
-    (energized_tiles_part_one, *energized_tiles_part_two.iter().max().unwrap())
+/// Draw the tiles energized (#) by the beam of part one, or by the best beam of part two.
+#[cfg(feature = "render")]
+pub fn render(data: &str, part: Part) -> Result<Canvas> {
+    let grid = parse(data)?;
+    let (position, direction) = match part {
+        Part::Two => edge_entries(&grid)
+            .into_iter()
+            .max_by_key(| &entry | count_energized(&energized_tiles(&grid, entry)))
+            .expect("a grid has at least one edge tile"),
+        _ => ((0, 0), b'E')
+    };
+    let energized = energized_tiles(&grid, (position, direction));
+
+    let tiles = Grid::from_fn(grid.width(), grid.height(), | tile | match (grid[tile], energized[tile]) {
+        _ if tile == position => Tile::new(direction as char, Style::Accent),
+        (b'.', true) => Tile::new('#', Style::Primary),
+        (b'.', false) => Tile::new('.', Style::Faint),
+        (symbol, true) => Tile::new(symbol as char, Style::Primary),
+        (symbol, false) => Tile::new(symbol as char, Style::Plain)
+    });
+
+    let caption = format!(
+        "#: {} energized tiles by the beam that enters at {position:?} heading {}.",
+        count_energized(&energized),
+        direction as char
+    );
+    Ok(Canvas::new(tiles, caption))
 }
 
 
-fn breadth_first_search(graph: &HashMap<(isize, isize), u8>, start: ((isize, isize), u8)) -> usize {
-    let (x_max, y_max) = *graph.keys().max().unwrap();
-
-    let mut queue: VecDeque<((isize, isize), u8)> = VecDeque::from([start]);
-    let mut visited: HashSet<((isize, isize), u8)> = HashSet::new();
-    let mut energized: HashSet<(isize, isize)> = HashSet::new();
-
-    while let Some((mut position, direction)) = queue.pop_front() {
-        if !visited.insert((position, direction)) {
-            continue;  // We got back to a path that we already visited.
-        }
-        energized.insert(position);
-
-        match direction {
-            b'N' if position.1 > 0 => position.1 -= 1,
-            b'E' if position.0 < x_max => position.0 += 1,
-            b'S' if position.1 < y_max => position.1 += 1,
-            b'W' if position.0 > 0 => position.0 -= 1,
-            _ => continue // New position would be outside of the board.
-        }
-
-        match (direction, graph.get(&position).unwrap()) {
-            (b'N', b'/') => queue.push_back((position, b'E')),
-            (b'N', b'\\') => queue.push_back((position, b'W')),
-            (b'N', b'-') => { queue.push_back((position, b'W')); queue.push_back((position, b'E')); },
-            (b'N', _) => queue.push_back((position, b'N')),
-            (b'E', b'/') => queue.push_back((position, b'N')),
-            (b'E', b'\\') => queue.push_back((position, b'S')),
-            (b'E', b'|') => { queue.push_back((position, b'N')); queue.push_back((position, b'S')); },
-            (b'E', _) => queue.push_back((position, b'E')),
-            (b'S', b'/') => queue.push_back((position, b'W')),
-            (b'S', b'\\') => queue.push_back((position, b'E')),
-            (b'S', b'-') => { queue.push_back((position, b'W')); queue.push_back((position, b'E')); },
-            (b'S', _) => queue.push_back((position, b'S')),
-            (b'W', b'/') => queue.push_back((position, b'S')),
-            (b'W', b'\\') => queue.push_back((position, b'N')),
-            (b'W', b'|') => { queue.push_back((position, b'N')); queue.push_back((position, b'S')); },
-            (b'W', _) => queue.push_back((position, b'W')),
-            _ => unreachable!()
+/// Every tile at the edge together with the direction into the grid, corners come twice.
+fn edge_entries(grid: &Grid<u8>) -> Vec<(Position, u8)> {
+    let (x_max, y_max) = (grid.width() - 1, grid.height() - 1);
+
+    (0..=y_max)
+        .flat_map(| y | [((0, y), b'E'), ((x_max, y), b'W')])
+        .chain((0..=x_max).flat_map(| x | [((x, 0), b'S'), ((x, y_max), b'N')]))
+        .collect()
+}
+
+fn count_energized(energized: &Grid<bool>) -> usize {
+    energized.iter().filter(| (_, &energized) | energized).count()
+}
+
+
+/// Follow the beam that enters the tile at `start` heading in a direction,
+/// and mark the tiles that it passes through.
+fn energized_tiles(grid: &Grid<u8>, start: (Position, u8)) -> Grid<bool> {
+    // Each state is a tile together with the direction in which the beam entered it.
+    let successors = | &(position, direction): &(Position, u8) | {
+        let next_directions: &[u8] = match (direction, grid[position]) {
+            (b'N', b'/') => b"E",
+            (b'N', b'\\') => b"W",
+            (b'N', b'-') => b"WE",
+            (b'E', b'/') => b"N",
+            (b'E', b'\\') => b"S",
+            (b'E', b'|') => b"NS",
+            (b'S', b'/') => b"W",
+            (b'S', b'\\') => b"E",
+            (b'S', b'-') => b"WE",
+            (b'W', b'/') => b"S",
+            (b'W', b'\\') => b"N",
+            (b'W', b'|') => b"NS",
+            (b'N', _) => b"N",
+            (b'E', _) => b"E",
+            (b'S', _) => b"S",
+            _ => b"W"
         };
+
+        next_directions.iter().filter_map(move | &next_direction | {
+            let step = match next_direction {
+                b'N' => (0, -1),
+                b'E' => (1, 0),
+                b'S' => (0, 1),
+                _ => (-1, 0)
+            };
+            // Beams that leave the grid are gone.
+            grid.step(position, step).map(| next | (next, next_direction))
+        })
+    };
+
+    let mut energized: Grid<bool> = Grid::filled(grid.width(), grid.height(), false);
+    for ((position, _direction), _distance) in Bfs::new(start, successors) {
+        energized[position] = true;
     }
-    energized.len() - 1
-}
\ No newline at end of file
+    energized
+}
+
+
+/// Generate a square contraption of mirrors and splitters in empty space.
+/// The size is the width and height of the contraption.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(| _ | {
+            (0..size)
+                .map(| _ | if rng.chance(0.1) { rng.pick_char("/\\|-") } else { '.' })
+                .collect::<String>() + "\n"
+        })
+        .collect()
+}
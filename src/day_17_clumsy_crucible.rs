@@ -1,50 +1,112 @@
-use std::cmp::Reverse;
-use std::collections::{BinaryHeap, BTreeMap};
+#[cfg(feature = "fs")]
 use std::fs;
 
-pub fn determine_path_of_minimal_heat_loss(file_path: &str) -> (isize, isize) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::grid::{Grid, Position};
+use crate::util::random::Rng;
+use crate::util::search::Dijkstra;
 
-    let mut graph: BTreeMap<(isize, isize), isize> = BTreeMap::new();
-    for (y, line) in data.lines().enumerate() {
-        for (x, character) in line.chars().enumerate() {
-            graph.insert(
-                (x as isize, y as isize), character.to_digit(10).unwrap() as isize
-            );
-        }
+pub struct ClumsyCrucible;
+
+impl Solver for ClumsyCrucible {
+    fn day(&self) -> u8 { 17 }
+
+    fn title(&self) -> &'static str { "Clumsy Crucible" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Path of Minimum Heat Loss 1", "Path of Minimum Heat Loss 2"] }
+
+    fn input_file(&self) -> &'static str { "day_17_clumsy_crucible.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn render(&self, data: &str, part: Part) -> Option<Result<Canvas>> {
+        Some(render(data, part))
     }
+}
 
-    let finish: (isize, isize) = *graph.keys().max().unwrap();
-    (
-        dijkstra(&graph, (0, 0), finish, 1, 3),
-        dijkstra(&graph, (0, 0), finish, 4, 10)
-    )
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_path_of_minimal_heat_loss(file_path: &str) -> Result<(isize, isize)> {
+    solve(&fs::read_to_string(file_path)?)
 }
 
+pub fn solve(data: &str) -> Result<(isize, isize)> {
+    let graph = parse(data)?;
+    Ok((part_one(&graph)?, part_two(&graph)?))
+}
 
-fn dijkstra(
-    graph: &BTreeMap<(isize, isize), isize>,
-    start: (isize, isize),
-    finish: (isize, isize),
-    min_step: isize,
-    max_step: isize
-) -> isize {
-    // Dijkstra's algorithm to find path of minimum heat loss.
+/// Parse the heat loss of each city block.
+pub fn parse(data: &str) -> Result<Grid<u8>> {
+    Grid::parse(data, | character | character.to_digit(10).map(| digit | digit as u8))
+}
 
-    let mut route = BTreeMap::new();
-    let mut queue = BinaryHeap::new();
+/// A crucible moves at most three blocks in a straight line.
+pub fn part_one(grid: &Grid<u8>) -> Result<isize> {
+    let finish: Position = (grid.width() - 1, grid.height() - 1);
+    dijkstra(grid, (0, 0), finish, 1, 3).map(| (heat_loss, _path) | heat_loss)
+}
 
-    // Reverse BinaryHeap acts as Min-heap
-    queue.push(Reverse((0, start, (0, 0))));
+/// An ultra crucible moves at least four and at most ten blocks in a straight line.
+pub fn part_two(grid: &Grid<u8>) -> Result<isize> {
+    let finish: Position = (grid.width() - 1, grid.height() - 1);
+    dijkstra(grid, (0, 0), finish, 4, 10).map(| (heat_loss, _path) | heat_loss)
+}
 
-    while let Some(Reverse((heat_loss, current, prev_dir))) = queue.pop() {
-        if current == finish {
-            return heat_loss;
-        }
+/// Draw the path of minimum heat loss of the crucible, or of the ultra crucible of part two.
+#[cfg(feature = "render")]
+pub fn render(data: &str, part: Part) -> Result<Canvas> {
+    let grid = parse(data)?;
+    let finish: Position = (grid.width() - 1, grid.height() - 1);
+    let (heat_loss, path) = match part {
+        Part::Two => dijkstra(&grid, (0, 0), finish, 4, 10)?,
+        _ => dijkstra(&grid, (0, 0), finish, 1, 3)?
+    };
 
-        if route.get(&(current, prev_dir)).is_some_and(| &loss | loss < heat_loss) {
-            continue;  // We already have a better route to this point
+    let mut tiles: Grid<Tile> = grid.map(| &heat_loss | Tile::new((b'0' + heat_loss) as char, Style::Faint));
+    tiles[(0, 0)].style = Style::Accent;
+    for (&(mut position, _), &(next, direction)) in path.iter().zip(&path[1..]) {
+        let arrow = match direction {
+            (0, -1) => '^',
+            (1, 0) => '>',
+            (0, 1) => 'v',
+            _ => '<'
+        };
+        while position != next {
+            position = grid.step(position, direction).expect("the path stays in the grid");
+            tiles[position] = Tile::new(arrow, Style::Primary);
         }
+    }
+
+    Ok(Canvas::new(tiles, format!("The path from the top-left block has a heat loss of {heat_loss}.")))
+}
+
+
+/// A position together with the direction in which the crucible arrived there.
+type State = (Position, (isize, isize));
+
+/// Find the path of minimum heat loss with Dijkstra's algorithm, returns the heat loss and the states of the path.
+/// The crucible moves between `min_step` and `max_step` blocks in a straight line, and then has to turn.
+fn dijkstra(
+    grid: &Grid<u8>,
+    start: Position,
+    finish: Position,
+    min_step: isize,
+    max_step: isize
+) -> Result<(isize, Vec<State>)> {
+    let successors = | &(current, prev_dir): &State | {
+        let mut next_states: Vec<(State, isize)> = Vec::new();
 
         for direction in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
             if prev_dir == direction || prev_dir == (-direction.0, -direction.1) {
@@ -53,32 +115,36 @@ fn dijkstra(
                 continue;
             }
 
-            let mut next_heat_loss: isize = heat_loss;
+            let mut heat_loss: isize = 0;
             for distance in 1..=max_step {
-                // TODO find cleaner method of adding all the heat losses.
-                //      currently we just take this part of the loop until distance < min_step
-
-                let next = (
-                    current.0 + direction.0 * distance,
-                    current.1 + direction.1 * distance
-                );
-
-                next_heat_loss += match graph.get(&next) {
-                    Some(loss) => loss,
-                    None => continue  // next step moved outside graph bounds.
+                let Some(next) = grid.step(current, (direction.0 * distance, direction.1 * distance)) else {
+                    break;  // next step moved outside graph bounds.
                 };
+                heat_loss += grid[next] as isize;
 
-                if distance < min_step {
-                    continue;  // Need to take more steps in the same direction.
-                }
-
-                if route.get(&(next, direction)).unwrap_or(&isize::MAX) > &next_heat_loss {
-                    // Insert if new route or improved compared to previous.
-                    queue.push(Reverse((next_heat_loss, next, direction)));
-                    route.insert((next, direction), next_heat_loss);
+                if distance >= min_step {
+                    next_states.push(((next, direction), heat_loss));
                 }
             }
         }
-    }
-    unreachable!()  // We should find the finish before the queue is empty.
-}
\ No newline at end of file
+        next_states
+    };
+
+    let mut search = Dijkstra::new((start, (0, 0)), successors);
+    let (state, heat_loss) = search
+        .find(| ((position, _), _) | *position == finish)
+        .ok_or_else(|| Error::unsolvable(format!("the crucible can not reach {finish:?} in steps of {min_step} to {max_step}")))?;
+
+    let path = search.reached().path(&state).expect("a found state is reached");
+    Ok((heat_loss, path))
+}
+
+
+/// Generate a square map of the heat loss in each block.
+/// The size is the width and height of the map, at least five so that an ultra crucible can reach the end.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5);
+    (0..size)
+        .map(| _ | (0..size).map(| _ | rng.pick_char("123456789")).collect::<String>() + "\n")
+        .collect()
+}
@@ -1,94 +1,158 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
-pub fn determine_lava_volume(file_path: &str) -> (isize, isize) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-    let (vertices, circumference) = generate_vertices_part_one(&data);
-    let area_part_one = shoelace_area(vertices) + circumference / 2 + 1;
+pub struct LavaductLagoon;
 
-    let (vertices, circumference) = generate_vertices_part_two(&data);
-    let area_part_two = shoelace_area(vertices) + circumference / 2 + 1;
+impl Solver for LavaductLagoon {
+    fn day(&self) -> u8 { 18 }
 
-    (area_part_one, area_part_two)
-}
+    fn title(&self) -> &'static str { "Lavaduct Lagoon" }
 
+    fn labels(&self) -> [&'static str; 2] { ["Lagoon Volume 1", "Lagoon Volume 2"] }
 
-fn generate_vertices_part_one(data: &str) -> (Vec<(isize, isize)>, isize) {
-    let mut vertices:  Vec<(isize, isize)> = Vec::new();
-    let mut circumference = 0;
+    fn input_file(&self) -> &'static str { "day_18_lavaduct_lagoon.txt" }
 
-    let mut previous_vertex = (0, 0);
-    vertices.push(previous_vertex);
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
 
-    for line in data.lines() {
-        let line_data: Vec<&str> = line.split_whitespace().collect();
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_lava_volume(file_path: &str) -> Result<(i64, i64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
 
-        let direction = match line_data[0] {
-            "U" => (0, 1),
-            "R" => (1, 0),
-            "D" => (0, -1),
-            "L" => (-1, 0),
-            _ => unreachable!()
-        };
+pub fn solve(data: &str) -> Result<(i64, i64)> {
+    let dig_plan = parse(data)?;
+    Ok((part_one(&dig_plan)?, part_two(&dig_plan)?))
+}
 
-        let distance: isize = line_data[1].parse().unwrap();
-        circumference += distance;
+/// Direction and distance of a single dig instruction.
+pub type Instruction = ((i64, i64), i64);
 
-        let next_vertex = (
-            previous_vertex.0 + direction.0 * distance,
-            previous_vertex.1 + direction.1 * distance
-        );
+/// The dig plan as read in part one, and as decoded from the colors in part two.
+pub struct DigPlan {
+    instructions: Vec<Instruction>,
+    color_instructions: Vec<Instruction>
+}
 
-        vertices.push(next_vertex);
-        previous_vertex = next_vertex;
+pub fn parse(data: &str) -> Result<DigPlan> {
+    let mut instructions: Vec<Instruction> = Vec::new();
+    let mut color_instructions: Vec<Instruction> = Vec::new();
+
+    for line in util::lines(data) {
+        instructions.push(parse_instruction(&line)?);
+        color_instructions.push(parse_color_instruction(&line)?);
     }
 
-    (vertices, circumference)
+    Ok(DigPlan { instructions, color_instructions })
 }
 
+pub fn part_one(dig_plan: &DigPlan) -> Result<i64> {
+    lagoon_area(&dig_plan.instructions)
+}
 
-fn generate_vertices_part_two(data: &str) -> (Vec<(isize, isize)>, isize) {
-    let mut vertices:  Vec<(isize, isize)> = Vec::new();
-    let mut circumference = 0;
+pub fn part_two(dig_plan: &DigPlan) -> Result<i64> {
+    lagoon_area(&dig_plan.color_instructions)
+}
 
-    let mut previous_vertex = (0, 0);
-    vertices.push(previous_vertex);
 
-    for line in data.lines() {
-        let (_, hex) = line.split_once('#').unwrap();
+fn parse_instruction(line: &Line) -> Result<Instruction> {
+    let line_data: Vec<&str> = line.text.split_whitespace().collect();
+    let [direction, distance, ..] = line_data[..] else {
+        return Err(line.error(line.text, "expected \"<direction> <distance> <color>\""));
+    };
+
+    let direction = match direction {
+        "U" => (0, 1),
+        "R" => (1, 0),
+        "D" => (0, -1),
+        "L" => (-1, 0),
+        _ => return Err(line.unknown_symbol(direction))
+    };
 
-        let direction = match hex.as_bytes()[5] {
-            b'3' => (0, 1),  // Up
-            b'0' => (1, 0),  // Right
-            b'1' => (0, -1), // Down
-            b'2' => (-1, 0), // Left
-            _ => unreachable!()
-        };
+    Ok((direction, parse_distance(line, distance, line.parse::<u32>(distance)?)?))
+}
+
+
+fn parse_color_instruction(line: &Line) -> Result<Instruction> {
+    let (_, hex) = line.split_once(line.text, "#")?;
+    let (Some(distance), Some(direction)) = (hex.get(0..5), hex.get(5..6)) else {
+        return Err(line.error(hex, "expected a color of 6 hexadecimal digits"));
+    };
+
+    let direction = match direction {
+        "3" => (0, 1),  // Up
+        "0" => (1, 0),  // Right
+        "1" => (0, -1), // Down
+        "2" => (-1, 0), // Left
+        _ => return Err(line.unknown_symbol(direction))
+    };
+
+    let meters = u32::from_str_radix(distance, 16)
+        .map_err(| _ | line.error(distance, format!("invalid hexadecimal number {distance:?}")))?;
+
+    Ok((direction, parse_distance(line, distance, meters)?))
+}
 
-        let distance = isize::from_str_radix(hex.get(0..5).unwrap(), 16).unwrap();
-        circumference += distance;
+
+fn parse_distance(line: &Line, part: &str, meters: u32) -> Result<i64> {
+    // Every instruction digs at least one meter, otherwise the trench could double back on itself.
+    match meters {
+        0 => Err(line.error(part, "expected a distance of at least one meter")),
+        _ => Ok(meters.into())
+    }
+}
+
+
+fn lagoon_area(instructions: &[Instruction]) -> Result<i64> {
+    let mut vertices:  Vec<(i64, i64)> = Vec::new();
+    let mut circumference: i64 = 0;
+
+    let mut previous_vertex = (0, 0);
+    vertices.push(previous_vertex);
+
+    // The distances are positive and fit a u32, so only the sums can overflow.
+    for &(direction, distance) in instructions {
+        circumference = circumference.checked_add(distance).ok_or_else(too_large)?;
 
         let next_vertex = (
-            previous_vertex.0 + direction.0 * distance,
-            previous_vertex.1 + direction.1 * distance
+            previous_vertex.0.checked_add(direction.0 * distance).ok_or_else(too_large)?,
+            previous_vertex.1.checked_add(direction.1 * distance).ok_or_else(too_large)?
         );
 
         vertices.push(next_vertex);
         previous_vertex = next_vertex;
     }
 
-    (vertices, circumference)
+    shoelace_area(vertices)?
+        .checked_add(circumference / 2 + 1)
+        .ok_or_else(too_large)
 }
 
 
-fn shoelace_area(vertices: Vec<(isize, isize)>) -> isize {
+fn shoelace_area(vertices: Vec<(i64, i64)>) -> Result<i64> {
     // Calculate the shoelace are held within the vertices.
-    let mut total = 0;
+    let mut total: i64 = 0;
 
     for idx in 0..(vertices.len() - 1) {
-        total +=
-            vertices[idx].0 * vertices[idx + 1].1
-            - vertices[idx].1 * vertices[idx + 1].0;
+        let (x1, y1) = vertices[idx];
+        let (x2, y2) = vertices[idx + 1];
+        total = x1.checked_mul(y2)
+            .zip(y1.checked_mul(x2))
+            .and_then(| (a, b) | a.checked_sub(b))
+            .and_then(| cross | total.checked_add(cross))
+            .ok_or_else(too_large)?;
     }
 
     // NOTE: This version assumes that vertices.first() == vertices.last()
@@ -96,5 +160,68 @@ fn shoelace_area(vertices: Vec<(isize, isize)>) -> isize {
     // total += vertices[vertices.len() - 1].0 * vertices[0].1
     //     - vertices[vertices.len() - 1].1 * vertices[0].0;
 
-    total.abs() / 2
-}
\ No newline at end of file
+    (total / 2).checked_abs().ok_or_else(too_large)
+}
+
+
+fn too_large() -> Error {
+    Error::unsolvable("the lagoon does not fit an i64")
+}
+
+
+/// Generate a dig plan of two different lagoons, one in the directions and one in the colors.
+/// The size is the number of columns of the lagoons, which have four instructions per column.
+/// The trench of either lagoon never touches itself.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let columns = size.max(2);
+    let plan = lagoon(rng, columns, 10);
+    let color_plan = lagoon(rng, columns, 0xfffff / 8);
+
+    plan
+        .iter()
+        .zip(&color_plan)
+        .map(| (&(direction, distance), &(color_direction, color_distance)) | {
+            let code = "RDLU".find(color_direction).unwrap();
+            format!("{direction} {distance} (#{color_distance:05x}{code})\n")
+        })
+        .collect()
+}
+
+
+/// A lagoon without holes, where column x spans the rows top[x] to bottom[x] of the trench.
+/// The bottom is below the tops of the neighbouring columns, so that the trench never touches itself.
+/// Every instruction digs at least one meter, so every lagoon of the same columns has as many instructions.
+fn lagoon(rng: &mut Rng, columns: usize, scale: usize) -> Vec<(char, usize)> {
+    let mut top: Vec<usize> = vec![4 * scale * columns];
+    for x in 1..columns {
+        let step = rng.range(1..scale + 1);
+        top.push(if rng.chance(0.5) { top[x - 1] + step } else { top[x - 1] - step });
+    }
+
+    let mut bottom: Vec<usize> = Vec::new();
+    for x in 0..columns {
+        let lowest_top = top[x.saturating_sub(2)..(x + 3).min(columns)].iter().max().unwrap();
+        let mut row = lowest_top + rng.range(1..scale + 1);
+        if x > 0 && row == bottom[x - 1] {
+            row += 1;
+        }
+        bottom.push(row);
+    }
+
+    let widths: Vec<usize> = (0..columns).map(| _ | rng.range(1..scale + 1)).collect();
+    let vertical = | from: usize, to: usize | (if to > from { 'D' } else { 'U' }, from.abs_diff(to));
+    let mut plan: Vec<(char, usize)> = Vec::new();
+
+    // Along the top from left to right, down the right side, back along the bottom and up the left side.
+    for x in 1..columns {
+        plan.push(('R', widths[x]));
+        plan.push(vertical(top[x - 1], top[x]));
+    }
+    plan.push(vertical(top[columns - 1], bottom[columns - 1]));
+    for x in (1..columns).rev() {
+        plan.push(('L', widths[x]));
+        plan.push(vertical(bottom[x], bottom[x - 1]));
+    }
+    plan.push(vertical(bottom[0], top[0]));
+    plan
+}
@@ -1,37 +1,130 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "fs")]
 use std::fs;
 
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
+
 type Workflows<'a> = HashMap<&'a str, Instructions<'a>>;
 type Rating = HashMap<u8, u32>;
 type PossibleRatings = [(u32, u32); 4];
 
 
-pub fn determine_rating_numbers(file_path: &str) -> (u32, u64) {
-    let data: String  = fs::read_to_string(file_path).unwrap();
+pub struct Aplenty;
+
+impl Solver for Aplenty {
+    fn day(&self) -> u8 { 19 }
 
-    let (workflows, ratings) = data.split_once("\n\n").unwrap();
+    fn title(&self) -> &'static str { "Aplenty" }
 
-    let workflows = parse_workflows(workflows);
-    let ratings = parse_ratings(ratings);
+    fn labels(&self) -> [&'static str; 2] { ["Rating Numbers of Accepted Parts", "Distinct Combinations of Ratings"] }
 
+    fn input_file(&self) -> &'static str { "day_19_aplenty.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_rating_numbers(file_path: &str) -> Result<(u32, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u32, u64)> {
+    let system = parse(data)?;
+    Ok((part_one(&system)?, part_two(&system)?))
+}
+
+/// The workflows and the ratings of the machine parts.
+pub struct System<'a> {
+    workflows: Workflows<'a>,
+    ratings: Vec<Rating>
+}
+
+pub fn parse(data: &str) -> Result<System<'_>> {
+    // The workflows and the ratings are separated by an empty line.
+    let lines: Vec<Line> = util::lines(data).collect();
+    let (workflows, ratings) = match lines.iter().position(| line | line.text.is_empty()) {
+        Some(index) => (&lines[..index], &lines[index + 1..]),
+        None => return Err(Error::parse(lines.len(), 0, "expected an empty line after the workflows"))
+    };
+
+    let workflows = parse_workflows(workflows)?;
+    let ratings = parse_ratings(ratings)?;
+
+    if !workflows.contains_key("in") {
+        return Err(Error::unsolvable("there is no workflow named \"in\""));
+    }
+    check_for_cycles(&workflows)?;
+
+    Ok(System { workflows, ratings })
+}
+
+/// Sum the ratings of the accepted parts.
+pub fn part_one(system: &System) -> Result<u32> {
     let mut ratings_part_one = 0;
-    for rating in ratings.iter() {
-        ratings_part_one += trace_outcome(rating, &workflows);
+    for rating in system.ratings.iter() {
+        ratings_part_one += trace_outcome(rating, &system.workflows)?;
     }
+    Ok(ratings_part_one)
+}
 
-    (ratings_part_one, distinct_combinations_of_ratings(&workflows))
+/// Count all combinations of ratings that would be accepted.
+pub fn part_two(system: &System) -> Result<u64> {
+    distinct_combinations_of_ratings(&system.workflows)
 }
 
 
-fn parse_workflows(data: &str) -> HashMap<&str, Instructions> {
-    let mut workflows: HashMap<&str, Instructions> = HashMap::new();
+fn parse_workflows<'a>(lines: &[Line<'a>]) -> Result<Workflows<'a>> {
+    let mut workflows: Workflows = HashMap::new();
 
-    for line in data.lines() {
-        let (location, instructions) = line.split_once('{').unwrap();
-        let instructions = parse_instructions(instructions);
+    for line in lines {
+        let (location, instructions) = line.split_once(line.text, "{")?;
+        let instructions = parse_instructions(line, instructions)?;
         workflows.insert(location, instructions);
     }
-    workflows
+    Ok(workflows)
+}
+
+/// Check that no workflow reachable from "in" leads back to itself, a part in such a loop is never accepted or rejected.
+/// The search keeps its own stack, because a long chain of workflows would overflow the call stack.
+fn check_for_cycles(workflows: &Workflows) -> Result<()> {
+    let mut finished: HashSet<&str> = HashSet::new();
+    let mut on_path: HashSet<&str> = HashSet::from(["in"]);
+    let mut path: Vec<(&str, usize)> = vec![("in", 0)];
+
+    while let Some((name, next_target)) = path.last_mut() {
+        let instructions = &workflows[*name];
+        let target = instructions.rules.iter().map(| rule | rule.target).chain([instructions.else_]).nth(*next_target);
+        *next_target += 1;
+
+        match target {
+            None => {
+                on_path.remove(*name);
+                finished.insert(*name);
+                path.pop();
+            },
+            Some("A" | "R") => (),
+            Some(target) if on_path.contains(target) => {
+                return Err(Error::unsolvable(format!("the workflow {target:?} leads back to itself")));
+            },
+            // A missing workflow is reported when a part is sent to it.
+            Some(target) if finished.contains(target) || !workflows.contains_key(target) => (),
+            Some(target) => {
+                on_path.insert(target);
+                path.push((target, 0));
+            }
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug)]
@@ -56,11 +149,11 @@ impl<'a> Instructions<'a> {
         mut ratings: [(u32, u32); 4],
         workflows: &Workflows,
         possible_ratings: &mut Vec<PossibleRatings>
-    ) {
+    ) -> Result<()> {
         let mut resolve = | target, rating | match target {
-            "R" => {},
-            "A" => possible_ratings.push(rating),
-            target => workflows.get(target).unwrap().find_combinations(rating, workflows, possible_ratings)
+            "R" => Ok(()),
+            "A" => { possible_ratings.push(rating); Ok(()) },
+            target => workflow(workflows, target)?.find_combinations(rating, workflows, possible_ratings)
         };
 
         for Rule { category, operation, value, target} in &self.rules {
@@ -70,15 +163,24 @@ impl<'a> Instructions<'a> {
                 Some(y) => y
             };
 
-            resolve(*target, update(ratings.clone(), *category, y));
+            resolve(*target, update(ratings, *category, y))?;
+            if y == x {
+                return Ok(());  // The rule takes all remaining ratings, so the next rules are never reached.
+            }
             ratings[category_to_index(category)] = range_compare(x, y);
         }
 
-        resolve(self.else_, ratings);
+        resolve(self.else_, ratings)
     }
 }
 
-fn parse_instructions(instructions: &str) -> Instructions {
+fn workflow<'a, 'b>(workflows: &'b Workflows<'a>, name: &str) -> Result<&'b Instructions<'a>> {
+    workflows
+        .get(name)
+        .ok_or_else(|| Error::unsolvable(format!("there is no workflow named {name:?}")))
+}
+
+fn parse_instructions<'a>(line: &Line<'a>, instructions: &'a str) -> Result<Instructions<'a>> {
     // FIXME changed from method to function because I lifetimes are hard.
 
     let mut instructions = instructions
@@ -87,26 +189,28 @@ fn parse_instructions(instructions: &str) -> Instructions {
         .rev();
 
     let mut rules: Vec<Rule> = Vec::new();
-    let else_ = instructions.next().unwrap();
+    let else_ = instructions.next().unwrap_or_default();  // split always returns at least one item.
 
     for instruction in instructions.rev() {
-        let (lhs, target) = instruction.split_once(':').unwrap();
+        let (lhs, target) = line.split_once(instruction, ":")?;
 
-        let value = lhs
-            .get(2..)
-            .unwrap()
-            .parse::<u32>()
-            .unwrap();
+        let (Some(category), Some(operation), Some(value)) = (lhs.get(..1), lhs.get(1..2), lhs.get(2..)) else {
+            return Err(line.error(lhs, "expected a condition like \"x>10\""));
+        };
+
+        if !"xmas".contains(category) {
+            return Err(line.unknown_symbol(category));
+        }
 
-        let lhs = lhs.as_bytes();
-        let category = lhs[0];
-        let operation = Op::from_byte(lhs[1]);
+        let value = line.parse::<u32>(value)?;
+        let category = category.as_bytes()[0];
+        let operation = Op::from_byte(operation.as_bytes()[0]).ok_or_else(|| line.unknown_symbol(operation))?;
 
         rules.push(Rule{ category, operation, value, target });
 
     };
 
-    Instructions { rules, else_ }
+    Ok(Instructions { rules, else_ })
 }
 
 
@@ -126,11 +230,11 @@ enum Op {
 
 impl Op {
 
-    fn from_byte(char: u8) -> Self {
+    fn from_byte(char: u8) -> Option<Self> {
         match char {
-            b'>' => Op::Gt,
-            b'<' => Op::Lt,
-            _ => unreachable!()
+            b'>' => Some(Op::Gt),
+            b'<' => Some(Op::Lt),
+            _ => None
         }
     }
 
@@ -151,41 +255,49 @@ impl Op {
     }
 }
 
-fn parse_ratings(data: &str) -> Vec<Rating> {
+fn parse_ratings(lines: &[Line]) -> Result<Vec<Rating>> {
     let mut ratings: Vec<_> = Vec::new();
 
-    for line in data.lines() {
+    for line in lines {
         let mut rating: HashMap<u8, u32> = HashMap::new();
-        for part in  line.trim_matches(|c| c == '{' || c == '}').split(',') {
-            let (category, value) = part.split_once('=').unwrap();
-            rating.insert(category.as_bytes()[0], value.parse::<u32>().unwrap());
+        for part in  line.text.trim_matches(|c| c == '{' || c == '}').split(',') {
+            let (category, value) = line.split_once(part, "=")?;
+
+            if category.len() != 1 || !"xmas".contains(category) {
+                return Err(line.unknown_symbol(category));
+            }
+            rating.insert(category.as_bytes()[0], line.parse::<u32>(value)?);
+        }
+
+        if rating.len() != 4 {
+            return Err(line.error(line.text, "expected a rating for x, m, a and s"));
         }
         ratings.push(rating);
     }
-    ratings
+    Ok(ratings)
 }
 
-fn trace_outcome(rating: &Rating, workflows: &Workflows) -> u32 {
-    let mut outcome = workflows["in"].apply(rating);
+fn trace_outcome(rating: &Rating, workflows: &Workflows) -> Result<u32> {
+    let mut outcome = workflow(workflows, "in")?.apply(rating);
 
     while outcome != "A" && outcome != "R" {
-        outcome = workflows[&outcome].apply(rating);
+        outcome = workflow(workflows, outcome)?.apply(rating);
     }
 
     if outcome == "A" {
-        rating.values().sum()
+        Ok(rating.values().sum())
     } else {
-        0
+        Ok(0)
     }
 }
 
-fn distinct_combinations_of_ratings(workflows: &Workflows) -> u64 {
+fn distinct_combinations_of_ratings(workflows: &Workflows) -> Result<u64> {
     // TODO cleanup later
     let possible_ratings: PossibleRatings = [(1, 4000); 4];
     let mut valid_rating: Vec<PossibleRatings> = Vec::new();
 
-    workflows["in"].find_combinations(possible_ratings, workflows, &mut valid_rating);
-    valid_rating.iter().map(valid_combinations).sum()
+    workflow(workflows, "in")?.find_combinations(possible_ratings, workflows, &mut valid_rating)?;
+    Ok(valid_rating.iter().map(valid_combinations).sum())
 }
 
 fn valid_combinations(possible_ratings: &PossibleRatings) -> u64 {
@@ -211,6 +323,49 @@ fn category_to_index(category: &u8) -> usize {
         b'm' => 1,
         b'a' => 2,
         b's' => 3,
-        _ => unreachable!()
+        _ => unreachable!()  // Categories are validated while parsing.
+    }
+}
+
+
+/// Generate a tree of workflows from "in", and as many parts with ratings up to 4000.
+/// The size is the number of workflows and of parts.
+/// The workflows form a tree, so that every part is either accepted or rejected.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut names: HashSet<String> = HashSet::from(["in".to_string()]);
+    let mut pending: Vec<String> = vec!["in".to_string()];
+    let mut workflows: Vec<String> = Vec::new();
+
+    while let Some(name) = pending.pop() {
+        let mut target = | rng: &mut Rng | {
+            if names.len() >= size || !rng.chance(0.6) {
+                return rng.pick(&["A", "R"]).to_string();
+            }
+            loop {
+                let name: String = (0..rng.range(2..4)).map(| _ | rng.pick_char("abcdefghijklmnopqrstuvwxyz")).collect();
+                if names.insert(name.clone()) {
+                    pending.push(name.clone());
+                    return name;
+                }
+            }
+        };
+
+        let mut rules: Vec<String> = (0..rng.range(1..5))
+            .map(| _ | {
+                let (category, operation, value) = (rng.pick_char("xmas"), rng.pick_char("<>"), rng.range(1..4000));
+                format!("{category}{operation}{value}:{}", target(rng))
+            })
+            .collect();
+        rules.push(target(rng));
+        workflows.push(format!("{name}{{{}}}\n", rules.join(",")));
     }
-}
\ No newline at end of file
+    rng.shuffle(&mut workflows);
+
+    let ratings: String = (0..size)
+        .map(| _ | {
+            let [x, m, a, s] = [(); 4].map(| _ | rng.range(1..4001));
+            format!("{{x={x},m={m},a={a},s={s}}}\n")
+        })
+        .collect();
+    format!("{}\n{ratings}", workflows.concat())
+}
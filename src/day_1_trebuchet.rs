@@ -1,8 +1,13 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
 use bstr::ByteSlice;
 use phf::phf_map;
 
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util::random::Rng;
+
 const NUMERIC: [u8; 10] = *b"0123456789";
 
 // Add just enough padding characters to deal with possible overlapping words.
@@ -18,20 +23,54 @@ static NUMBER_WORDS: phf::Map<&[u8], &[u8]> = phf_map! {
     b"nine" => b"n9ne"
 };
 
-pub fn determine_calibration_values(file_path: &str) -> (u32, u32) {
-    let mut data: Vec<u8> = fs::read(file_path).unwrap();
+pub struct Trebuchet;
+
+impl Solver for Trebuchet {
+    fn day(&self) -> u8 { 1 }
+
+    fn title(&self) -> &'static str { "Trebuchet" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Sum of Calibration Values 1", "Sum of Calibration Values 2"] }
+
+    fn input_file(&self) -> &'static str { "day_1_trebuchet.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_calibration_values(file_path: &str) -> Result<(u32, u32)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u32, u32)> {
+    let data = parse(data)?;
+    Ok((part_one(&data)?, part_two(&data)?))
+}
+
+pub fn parse(data: &str) -> Result<Vec<u8>> {
+    Ok(data.as_bytes().to_vec())
+}
 
-    // Part 1 before changing the words to numbers
-    let part_one = calculate_sum(&data);
+/// Part 1 before changing the words to numbers
+pub fn part_one(data: &[u8]) -> Result<u32> {
+    Ok(calculate_sum(data))
+}
 
-    // Part 2 change words to numbers and calculate again
+/// Part 2 change words to numbers and calculate again
+pub fn part_two(data: &[u8]) -> Result<u32> {
+    let mut data = data.to_vec();
     NUMBER_WORDS
         .into_iter()
         .for_each(| (word, num) | data = data.replace(word, num));
 
-    let part_two = calculate_sum(&data);
-
-    (part_one, part_two)
+    Ok(calculate_sum(&data))
 }
 
 
@@ -57,6 +96,31 @@ fn calculate_sum(data: &[u8]) -> u32 {
     number_sum
 }
 
+
+/// Generate lines of letters, digits and spelled out digits.
+/// The size is the number of lines, every line has at least one digit.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let words: Vec<&[u8]> = NUMBER_WORDS.keys().copied().collect();
+    let mut data: Vec<u8> = Vec::new();
+
+    for _ in 0..size {
+        let digit_at = rng.range(0..4);
+        for token in 0..4 {
+            match rng.range(0..3) {
+                _ if token == digit_at => data.push(*rng.pick(&NUMERIC[1..])),
+                0 => data.extend(rng.pick(&words).iter()),
+                1 => data.push(*rng.pick(&NUMERIC[1..])),
+                _ => for _ in 0..rng.range(1..5) {
+                    data.push(rng.pick_char("abcdefghijklmnopqrstuvwxyz") as u8);
+                }
+            }
+        }
+        data.push(b'\n');
+    }
+    String::from_utf8(data).unwrap()
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
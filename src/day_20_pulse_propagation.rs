@@ -1,14 +1,68 @@
 use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "fs")]
 use std::fs;
 
-use crate::util::lcm;
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+use crate::observe;
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Frames, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, number_theory};
+#[cfg(feature = "render")]
+use crate::util::grid::Grid;
+use crate::util::number_theory::Congruence;
+use crate::util::random::Rng;
 
-pub fn determine_pulse_product(file_path: &str) -> (usize, usize) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+/// The most pushes to wait for a high pulse on a trace in part two.
+/// The counters of the puzzle inputs have twelve bits, so they send a high pulse within 4096 pushes.
+const MAX_PUSHES: usize = 1 << 16;
 
-    let mut network = create_network(&data);
 
-    // Part One
+pub struct PulsePropagation;
+
+impl Solver for PulsePropagation {
+    fn day(&self) -> u8 { 20 }
+
+    fn title(&self) -> &'static str { "Pulse Propagation" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Pulse Product", "Fewest Presses to reach RX"] }
+
+    fn input_file(&self) -> &'static str { "day_20_pulse_propagation.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn animate(&self, data: &str, _part: Part, frames: Frames) -> Option<Result<Vec<Canvas>>> {
+        Some(animate(data, frames))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_pulse_product(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let network = parse(data)?;
+    Ok((part_one(&network)?, part_two(&network)?))
+}
+
+pub fn parse(data: &str) -> Result<Network<'_>> {
+    create_network(data)
+}
+
+/// Multiply the number of low and high pulses sent by pushing the button 1000 times.
+pub fn part_one(network: &Network) -> Result<u64> {
+    let mut network = network.clone();
     let mut low_pulse: usize = 0;
     let mut high_pulse: usize = 0;
 
@@ -18,61 +72,151 @@ pub fn determine_pulse_product(file_path: &str) -> (usize, usize) {
         high_pulse += high;
     }
 
-    // Part Two
-    // If all the traced values are "High" we send a signal to "rx"
-    // Find when each of these becomes "High" then lcm to find "rx"
+    Ok(low_pulse as u64 * high_pulse as u64)
+}
+
+/// If all the traced values are "High" we send a signal to "rx"
+/// Find when each of these becomes "High" and how many pushes it takes to become "High" again.
+/// The first push at which all of them are "High" follows from the Chinese Remainder Theorem.
+pub fn part_two(network: &Network) -> Result<u64> {
+    let mut network = network.clone();
     let mut tracers: Vec<&str> = Vec::new();
     for (name, module) in network.modules.iter() {
         if module.operation == Operation::Conjunction
             && network.receivers.get(name).is_some_and(| r | r.contains(&"rx")) {
-            tracers.extend(module.conj_states.keys());
+            tracers.extend(module.conj_states.keys().copied());
         }
     }
 
-    let mut button_pushes: Vec<usize> = Vec::new();
+    if tracers.is_empty() {
+        return Err(Error::unsolvable("there is no conjunction module that sends to \"rx\""));
+    }
+
+    let mut cycles: Vec<Congruence> = Vec::new();
+    let mut min_pushes: usize = 0;
 
     for trace in tracers.iter() {
         network.reset();
-        let mut n_pushes: usize = 0;
+        let first = pushes_until_high(&mut network, trace)?;
+        let period = pushes_until_high(&mut network, trace)?;
+        observe::value(trace, format!("first high after {first} pushes, then every {period} pushes"));
+
+        // The period is at least one push, so the congruence always exists.
+        cycles.extend(Congruence::new(first as u128, period as u128));
+        min_pushes = min_pushes.max(first);
+    }
+
+    number_theory::crt(cycles)
+        .and_then(| pushes | pushes.smallest_at_least(min_pushes as u128))
+        .and_then(| pushes | u64::try_from(pushes).ok())
+        .ok_or_else(|| Error::unsolvable("the traced modules are never high at the same time"))
+}
 
-        let mut high_pulse_on_trace = false;
-        while !high_pulse_on_trace {
-            let (_, _, pulse) = network.push_button(trace);
-            high_pulse_on_trace = pulse;
-            n_pushes += 1;
+
+/// Draw the state of the modules after each push of the button, a row per module in the order of their names.
+/// A flip-flop shows whether it is on, a conjunction shows the last pulse from each of its inputs: 1 for high.
+#[cfg(feature = "render")]
+pub fn animate(data: &str, frames: Frames) -> Result<Vec<Canvas>> {
+    let mut network = parse(data)?;
+    let mut names: Vec<&str> = network.modules
+        .iter()
+        .filter(| (_, module) | module.operation != Operation::None)
+        .map(| (&name, _) | name)
+        .collect();
+    names.sort();
+    let name_width = names.iter().map(| name | name.len()).max().unwrap_or(0);
+
+    let bit = | high: bool | match high {
+        true => Tile::new('1', Style::Primary),
+        false => Tile::new('0', Style::Faint)
+    };
+
+    let mut canvases: Vec<Canvas> = Vec::new();
+    let (mut low_pulses, mut high_pulses) = (0, 0);
+    for push in 0..=frames.last {
+        if push > 0 {
+            let (low, high, _) = network.push_button("");
+            low_pulses += low;
+            high_pulses += high;
+        }
+        if !frames.contains(push) {
+            continue;
         }
 
-        button_pushes.push(n_pushes);
+        let rows: Vec<Vec<Tile>> = names
+            .iter()
+            .map(| name | {
+                let module = &network.modules[name];
+                let kind = if module.operation == Operation::FlipFlop { '%' } else { '&' };
+                let mut row: Vec<Tile> = format!("{kind}{name:<name_width$} ")
+                    .chars()
+                    .map(| symbol | Tile::new(symbol, Style::Plain))
+                    .collect();
+
+                match module.operation {
+                    Operation::FlipFlop => row.push(bit(module.flip_state)),
+                    _ => {
+                        let mut inputs: Vec<(&str, bool)> = module.conj_states.iter().map(| (&input, &high) | (input, high)).collect();
+                        inputs.sort_unstable();
+                        row.extend(inputs.into_iter().map(| (_, high) | bit(high)))
+                    }
+                }
+                row
+            })
+            .collect();
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let tiles: Vec<Tile> = rows
+            .into_iter()
+            .flat_map(| mut row | {
+                row.resize(width, Tile::new(' ', Style::Faint));
+                row
+            })
+            .collect();
+
+        let caption = format!("Push {push}: {low_pulses} low and {high_pulses} high pulses sent.");
+        canvases.push(Canvas::new(Grid::new(width, names.len(), tiles), caption));
     }
-
-    (low_pulse * high_pulse, lcm(&button_pushes))
+    Ok(canvases)
 }
 
 
-fn create_network(data: &str) -> Network {
+fn pushes_until_high(network: &mut Network, trace: &str) -> Result<usize> {
+    // Push the button until a high pulse is seen on the trace, continuing from the current state.
+    let mut n_pushes: usize = 0;
+
+    let mut high_pulse_on_trace = false;
+    while !high_pulse_on_trace {
+        if n_pushes == MAX_PUSHES {
+            return Err(Error::unsolvable(format!("no high pulse on \"{trace}\" within {MAX_PUSHES} pushes")));
+        }
+        let (_, _, pulse) = network.push_button(trace);
+        high_pulse_on_trace = pulse;
+        n_pushes += 1;
+    }
+    Ok(n_pushes)
+}
+
+fn create_network(data: &str) -> Result<Network<'_>> {
     let mut network: Network = Network::new();
 
     // Parse the lines to create the initial network
-    for line in data.lines() {
-        let (sender, receiver) = line.split_once(" -> ").unwrap();
+    for line in util::lines(data) {
+        let (sender, receiver) = line.split_once(line.text, " -> ")?;
         let receiver: Vec<&str> = receiver.split(',').map(| rec | rec.trim()).collect();
 
-        match sender.as_bytes()[0] {
-            b'%' => {
-                let sender = sender.get(1..).unwrap();
-                network.modules.insert(sender, Module::new(Operation::FlipFlop));
-                network.receivers.insert(sender, receiver);
-            },
-            b'&' => {
-                let sender = sender.get(1..).unwrap();
-                network.modules.insert(sender, Module::new(Operation::Conjunction));
-                network.receivers.insert(sender, receiver);
-            },
-            _ => {
-                network.modules.insert(sender, Module::new(Operation::None));
-                network.receivers.insert(sender, receiver);
-            },
-        };
+        if let Some(sender) = sender.strip_prefix('%') {
+            network.modules.insert(sender, Module::new(Operation::FlipFlop));
+            network.receivers.insert(sender, receiver);
+        } else if let Some(sender) = sender.strip_prefix('&') {
+            network.modules.insert(sender, Module::new(Operation::Conjunction));
+            network.receivers.insert(sender, receiver);
+        } else if !sender.is_empty() {
+            network.modules.insert(sender, Module::new(Operation::None));
+            network.receivers.insert(sender, receiver);
+        } else {
+            return Err(line.error(sender, "expected a module name"));
+        }
     }
 
     // Parse the receivers to add the conjunction state logic.
@@ -84,12 +228,13 @@ fn create_network(data: &str) -> Network {
         }
     }
 
-    network
+    Ok(network)
 }
 
 
-#[derive(Debug)]
-struct Network<'a> {
+/// The modules of the network and the modules they send pulses to.
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
     modules: HashMap<&'a str, Module<'a>>,
     receivers: HashMap<&'a str, Vec<&'a str>>,
 }
@@ -120,7 +265,7 @@ impl<'a> Network<'a> {
         signals.push_back(("button", "broadcaster", false));
 
         while let Some((sender, receiver, sig)) = signals.pop_front() {
-            // println!("{sender} -{sig} -> {receiver}");  // Debug print.
+            observe::trace(|| format!("{sender} -{} -> {receiver}", if sig { "high" } else { "low" }));
 
             let module = match self.modules.get_mut(receiver) {
                 Some(m) => m,
@@ -132,7 +277,10 @@ impl<'a> Network<'a> {
                 None => continue  // Signal terminates here (can happen on FlipFlop)
             };
 
-            let next_receivers = self.receivers.get(receiver).unwrap();
+            let next_receivers = match self.receivers.get(receiver) {
+                Some(r) => r,
+                None => continue  // Module without receivers.
+            };
 
             match output {  // Add the pulses to the counters.
                 true => high_pulses += next_receivers.len(),
@@ -151,7 +299,7 @@ impl<'a> Network<'a> {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Module<'a> {
     operation: Operation,
     flip_state: bool,  // false = Low Signal, true = High Signal
@@ -176,9 +324,7 @@ impl<'a> Module<'a> {
     fn propagate_flipflop(&mut self, signal: bool) -> Option<bool> {
         match signal {
             true => None,
-            false if !self.flip_state => { self.flip_state = true; Some(true) },
-            false if self.flip_state => { self.flip_state = false; Some(false) },
-            _ => unreachable!()
+            false => { self.flip_state = !self.flip_state; Some(self.flip_state) }
         }
     }
 
@@ -191,7 +337,7 @@ impl<'a> Module<'a> {
     }
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 enum Operation {
     FlipFlop,
     Conjunction,
@@ -256,4 +402,51 @@ enum Operation {
 //     fn propagate(&mut self, sender: &str, signal: Pulse) -> Option<Pulse> {
 //         Some(signal)
 //     }
-// }
\ No newline at end of file
+// }
+
+
+/// Generate a network of binary counters that send a low pulse to "rx" when they all reset at the same push.
+/// The size is the number of counters, at most 31. Fewer counters have more bits, at most twelve,
+/// such that the product of their periods, and so the pushes until "rx" receives a low pulse, fits the answer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const LETTERS: &str = "abcdefghijklmnopqrstuvwxyz";
+    let counters = size.clamp(1, 31);
+    let bits = (63 / counters).min(12);
+
+    let mut names: Vec<String> = LETTERS
+        .chars()
+        .flat_map(| first | LETTERS.chars().map(move | second | format!("{first}{second}")))
+        .filter(| name | name != "rx")
+        .collect();
+    rng.shuffle(&mut names);
+
+    let output = names.pop().unwrap();
+    let mut modules: Vec<String> = vec![format!("&{output} -> rx\n")];
+    let mut starts: Vec<String> = Vec::new();
+
+    for _ in 0..counters {
+        // The conjunction resets the counter when the flip-flops of the set bits of the period are all high.
+        // The period is odd, so the first flip-flop always sends to the conjunction.
+        let period = rng.range(1 << (bits - 1)..1 << bits) | 1;
+        let flip_flops: Vec<String> = (0..bits).map(| _ | names.pop().unwrap()).collect();
+        let (conjunction, inverter) = (names.pop().unwrap(), names.pop().unwrap());
+
+        let mut resets: Vec<&str> = vec![&flip_flops[0]];
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut receivers: Vec<&str> = flip_flops.get(bit + 1).map(String::as_str).into_iter().collect();
+            match period >> bit & 1 == 1 {
+                true => receivers.push(&conjunction),
+                false => resets.push(flip_flop)
+            }
+            modules.push(format!("%{flip_flop} -> {}\n", receivers.join(", ")));
+        }
+        resets.push(&inverter);
+
+        modules.push(format!("&{conjunction} -> {}\n", resets.join(", ")));
+        modules.push(format!("&{inverter} -> {output}\n"));
+        starts.push(flip_flops[0].clone());
+    }
+    rng.shuffle(&mut modules);
+
+    format!("broadcaster -> {}\n{}", starts.join(", "), modules.concat())
+}
@@ -1,89 +1,234 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs;
 
-use crate::util::text_to_graph;
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::grid::{Grid, ORTHOGONAL, Position};
+use crate::util::polynomial::Polynomial;
+use crate::util::random::Rng;
+use crate::util::search::Bfs;
 
-type Graph = HashMap<(isize, isize), u8>;
+/// Number of steps the elf takes in part two.
+const STEPS: usize = 26501365;
 
+pub struct StepCounter;
 
-pub fn determine_visited_garden_plots(file_path: &str) -> (usize, usize) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+impl Solver for StepCounter {
+    fn day(&self) -> u8 { 21 }
 
-    let mut graph: Graph = text_to_graph(data);
-    let start = *graph
-        .iter()
-        .find(| ((_x, _y), char) | **char == b'S')
-        .unwrap()
-        .0;  // item 0 gets the x, y coordinates
+    fn title(&self) -> &'static str { "Step Counter" }
 
-    // Part One
-    let visited = breadth_first_search(&graph, start, 64);
-    let reachable_plots_part_one = visited
+    fn labels(&self) -> [&'static str; 2] { ["Reachable Garden Plots 1", "Reachable Garden Plots 2"] }
+
+    fn input_file(&self) -> &'static str { "day_21_step_counter.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn render(&self, data: &str, part: Part) -> Option<Result<Canvas>> {
+        Some(render(data, part))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_visited_garden_plots(file_path: &str) -> Result<(usize, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, u64)> {
+    let garden = parse(data)?;
+    Ok((part_one(&garden)?, part_two(&garden)?))
+}
+
+/// The garden plots and rocks, and the starting position of the elf.
+pub struct Garden {
+    grid: Grid<u8>,
+    start: Position
+}
+
+pub fn parse(data: &str) -> Result<Garden> {
+    let grid: Grid<u8> = Grid::from_symbols(data, "S.#")?;
+    let start = grid
+        .position(| &tile | tile == b'S')
+        .ok_or_else(|| Error::unsolvable("there is no start tile"))?;
+
+    Ok(Garden { grid, start })
+}
+
+/// Count the plots that can be reached in exactly 64 steps.
+pub fn part_one(garden: &Garden) -> Result<usize> {
+    let grid = &garden.grid;
+    let is_plot = | (x, y): (isize, isize) | {
+        x >= 0 && y >= 0 && grid.get((x as usize, y as usize)).is_some_and(| &tile | tile != b'#')
+    };
+
+    let visited = breadth_first_search(is_plot, garden.start, 64);
+    Ok(visited
         .values()
         .filter(| distance | *distance % 2 == 0)
-        .count();
-
-    // Part Two
-    let (x_max, y_max) = *graph.keys().max().unwrap();
-    assert_eq!(x_max, y_max);
-    let size = x_max + 1;  // +1 because the graph is zero-indexed.
+        .count())
+}
 
-    // Expand the graph by factor 5
-    for ((x, y), value) in graph.clone().iter() {
-        for (a, b) in itertools::iproduct!(-5..=5, -5..=5) {
-            graph.insert((x + a * size, y + b * size), *value);
-        }
+/// Count the plots that can be reached in exactly 26501365 steps in the infinitely repeating garden.
+/// The start row and column are free of rocks, so every `size` steps the reachable area grows by a
+/// ring of gardens and the count is a quadratic function of the number of gardens walked through.
+/// Fit it on the first three gardens and evaluate it at the target.
+pub fn part_two(garden: &Garden) -> Result<u64> {
+    let grid = &garden.grid;
+    if grid.width() != grid.height() {
+        return Err(Error::unsolvable("the garden is not square"));
     }
+    let size = grid.width();
+    let is_plot = | position: (isize, isize) | *grid.get_wrapped(position) != b'#';
 
-    // Find reachable for each max_distance
-    let mut reachable: Vec<usize> = Vec::new();
+    // Find reachable for each max_distance that ends at the same point of a garden as the target.
+    let mut reachable: Vec<i128> = Vec::new();
     for i in 0..3 {
-        let max_distance: u32 = 65 + i * size as u32;  // 65 is distance to first edge.
+        let max_distance: u32 = (STEPS % size + i * size) as u32;
 
         reachable.push(
-            breadth_first_search(&graph, start, max_distance)
+            breadth_first_search(is_plot, garden.start, max_distance)
                 .values()
                 .filter(| distance | *distance % 2 == max_distance % 2)
-                .count()
+                .count() as i128
         );
     }
 
-    let target = (26501365 - 65) / size as usize;
-    // Quadratic fit from WolframAlpha
-    // TODO implement quadratic fit in Rust.
-    let reachable_plots_part_two: usize = 3606 + 14377 * target + 14275 * target * target;
+    let target = (STEPS / size) as i128;
+    Polynomial::fit(&reachable)
+        .and_then(| polynomial | polynomial.evaluate(target))
+        .and_then(| plots | u64::try_from(plots).ok())
+        .ok_or_else(|| Error::unsolvable("the number of reachable plots overflows"))
+}
+
+/// Draw the plots (O) reachable in exactly 64 steps, or for part two the plots in the five by five gardens
+/// around the start that are reachable in the largest number of steps that part two fits the quadratic on.
+#[cfg(feature = "render")]
+pub fn render(data: &str, part: Part) -> Result<Canvas> {
+    let garden = parse(data)?;
+    let grid = &garden.grid;
+    let (gardens, steps) = match part {
+        Part::Two => (5, STEPS % grid.width() + 2 * grid.width()),
+        _ => (1, 64)
+    };
+
+    let repeats = part == Part::Two;
+    let is_plot = | (x, y): (isize, isize) | {
+        let inside = x >= 0 && y >= 0 && grid.get((x as usize, y as usize)).is_some();
+        (repeats || inside) && *grid.get_wrapped((x, y)) != b'#'
+    };
+    let visited = breadth_first_search(is_plot, garden.start, steps as u32);
+
+    let (width, height) = (grid.width(), grid.height());
+    let mut tiles: Grid<Tile> = Grid::from_fn(gardens * width, gardens * height, | (x, y) | {
+        match grid[(x % width, y % height)] {
+            b'#' => Tile::new('#', Style::Plain),
+            _ => Tile::new('.', Style::Faint)
+        }
+    });
+
+    // The start is in the middle garden.
+    let offset = | (x, y): (isize, isize) | {
+        let x = usize::try_from(x + (gardens / 2 * width) as isize).ok()?;
+        let y = usize::try_from(y + (gardens / 2 * height) as isize).ok()?;
+        Some((x, y))
+    };
+    let mut plots: usize = 0;
+    for (&position, _) in visited.iter().filter(| (_, &distance) | distance as usize % 2 == steps % 2) {
+        plots += 1;
+        if let Some(tile) = offset(position).and_then(| position | tiles.get_mut(position)) {
+            *tile = Tile::new('O', Style::Primary);
+        }
+    }
+
+    // The start is only drawn if it is not a reachable plot.
+    let start = offset((garden.start.0 as isize, garden.start.1 as isize)).expect("the start is in the middle garden");
+    if tiles[start].symbol == '.' {
+        tiles[start] = Tile::new('S', Style::Accent);
+    }
 
-    (reachable_plots_part_one, reachable_plots_part_two)
+    Ok(Canvas::new(tiles, format!("O: {plots} garden plots are reachable in exactly {steps} steps.")))
 }
 
+
+/// Distance from the start of every plot within `max_distance` steps.
+/// Positions are signed so that the search can leave the garden if `is_plot` allows it.
 fn breadth_first_search(
-    graph: &Graph, start: (isize, isize), max_distance: u32
+    is_plot: impl Fn((isize, isize)) -> bool, start: Position, max_distance: u32
 ) -> HashMap<(isize, isize), u32> {
-    let mut queue: VecDeque<((isize, isize), u32)> = VecDeque::from([(start, 0)]);
-    let mut visited: HashMap<(isize, isize), u32> = HashMap::new();
+    let successors = | &(x, y): &(isize, isize) | {
+        ORTHOGONAL
+            .into_iter()
+            .map(move | (dx, dy) | (x + dx, y + dy))
+            .filter(| &next | is_plot(next))
+    };
+
+    Bfs::new((start.0 as isize, start.1 as isize), successors)
+        .map(| (position, distance) | (position, distance as u32))
+        .take_while(| &(_, distance) | distance <= max_distance)
+        .collect()
+}
 
-    while let Some((position, distance)) = queue.pop_front() {
-        if distance > max_distance {
-            continue;
-        }
 
-        match visited.get(&position) {
-            Some(_) => continue,
-            None => visited.insert(position, distance)
-        };
+/// Generate a square garden with rocks, and the start in the middle.
+/// The size is the width and height of the garden, made odd so that the start is in the middle.
+/// The start row and column and the border are free of rocks, which the shortcut of part two relies on.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(5) | 1;
+    let middle = size / 2;
+
+    (0..size)
+        .map(| y | {
+            (0..size)
+                .map(| x | match (x, y) {
+                    _ if (x, y) == (middle, middle) => 'S',
+                    _ if x == middle || y == middle || x % (size - 1) == 0 || y % (size - 1) == 0 => '.',
+                    _ if rng.chance(0.12) => '#',
+                    _ => '.'
+                })
+                .collect::<String>() + "\n"
+        })
+        .collect()
+}
 
-        for direction in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
 
-            let next = (position.0 + direction.0, position.1 + direction.1);
-            match graph.get_key_value(&next) {
-                Some((coord, tile)) if *tile != b'#' => {
-                    queue.push_back((*coord, distance + 1));
-                },
-                Some(_) => continue,  // Rock
-                None => unreachable!(),  // Guard because we should never reach an edge.
-            };
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reachable_plots(is_plot: impl Fn((isize, isize)) -> bool, start: Position, steps: u32) -> usize {
+        breadth_first_search(is_plot, start, steps)
+            .values()
+            .filter(| distance | *distance % 2 == steps % 2)
+            .count()
     }
 
-    visited
+    #[test]
+    fn test_reachable_plots() {
+        let garden = parse(include_str!("../data/example/day_21_step_counter.txt")).unwrap();
+        let grid = &garden.grid;
+
+        let is_plot = | (x, y): (isize, isize) | {
+            x >= 0 && y >= 0 && grid.get((x as usize, y as usize)).is_some_and(| &tile | tile != b'#')
+        };
+        assert_eq!(reachable_plots(is_plot, garden.start, 6), 16);
+
+        // The infinitely repeating garden.
+        let is_plot = | position: (isize, isize) | *grid.get_wrapped(position) != b'#';
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            assert_eq!(reachable_plots(is_plot, garden.start, steps), expected);
+        }
+    }
 }
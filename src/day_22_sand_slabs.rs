@@ -1,44 +1,188 @@
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs;
+#[cfg(feature = "render")]
+use std::io;
 
 use itertools::Itertools;
 
-pub fn determine_safe_bricks(file_path: &str) -> (u16, u32) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::{Error, Result};
+use crate::observe;
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Frames, Style, Tile};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+#[cfg(feature = "render")]
+use crate::util::grid::Grid;
+use crate::util::random::Rng;
 
-    let mut bricks: Vec<Vec<u16>>  = data
-        .lines()
-        .map( | line | line
-            .split(&[',', '~'])
-            .map(| c| c.parse::<u16>().unwrap())
-            .collect::<Vec<u16>>()
-        )
+/// The bricks stand on a ground of this many cubes along x and y, much more than the 10 by 10 of the puzzle.
+const GROUND: u16 = 1000;
+
+/// The animation draws snapshots up to this many cubes high, the snapshot of the puzzle is a few hundred high.
+#[cfg(feature = "render")]
+const MAX_DRAWN_HEIGHT: usize = 1000;
+
+
+pub struct SandSlabs;
+
+impl Solver for SandSlabs {
+    fn day(&self) -> u8 { 22 }
+
+    fn title(&self) -> &'static str { "Sand Slabs" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Safe Bricks", "Fallen Bricks"] }
+
+    fn input_file(&self) -> &'static str { "day_22_sand_slabs.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+
+    #[cfg(feature = "render")]
+    fn animate(&self, data: &str, _part: Part, frames: Frames) -> Option<Result<Vec<Canvas>>> {
+        Some(animate(data, frames))
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_safe_bricks(file_path: &str) -> Result<(usize, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, u64)> {
+    let bricks = parse(data)?;
+    Ok((part_one(&bricks)?, part_two(&bricks)?))
+}
+
+/// The bricks as they are in the snapshot, from the lowest to the highest.
+pub fn parse(data: &str) -> Result<Vec<Vec<u16>>> {
+    Ok(util::lines(data)
+        .map(parse_brick)
+        .collect::<Result<Vec<Vec<u16>>>>()?
+        .into_iter()
         .sorted_by_key(| vec | vec[2])
-        .collect();
+        .collect())
+}
 
-    // Drop bricks to generate the real starting positions
-    drop_bricks(&mut bricks, &Vec::new());
+/// Count the bricks that can be disintegrated without any other brick falling.
+pub fn part_one(snapshot: &[Vec<u16>]) -> Result<usize> {
+    let fallen_bricks = fallen_bricks(&settle(snapshot)?)?;
+    Ok(fallen_bricks.into_iter().filter(| &fallen_bricks | fallen_bricks == 0).count())
+}
 
-    let mut safe_bricks: u16 = 0;
-    let mut sum_of_fallen_bricks: u32 = 0;
+/// Sum the number of bricks that fall for each disintegrated brick.
+pub fn part_two(snapshot: &[Vec<u16>]) -> Result<u64> {
+    let fallen_bricks = fallen_bricks(&settle(snapshot)?)?;
+    Ok(fallen_bricks.into_iter().map(| fallen_bricks | fallen_bricks as u64).sum())
+}
+
+
+/// Draw the bricks from the side while they settle one by one, from the lowest brick up.
+/// Step `n` has the lowest `n` bricks settled, the others are still where the snapshot has them.
+/// The left view looks along the y axis and the right view along the x axis, like the puzzle text.
+#[cfg(feature = "render")]
+pub fn animate(data: &str, frames: Frames) -> Result<Vec<Canvas>> {
+    let snapshot = parse(data)?;
+    let max = | coordinate: usize | snapshot.iter().map(| brick | brick[coordinate] as usize).max().unwrap_or(0);
+    let (width_x, width_y, height) = (max(3) + 1, max(4) + 1, max(5) + 1);
+    if height > MAX_DRAWN_HEIGHT {
+        let message = format!("the snapshot is {height} cubes high, only up to {MAX_DRAWN_HEIGHT} can be drawn");
+        return Err(Error::Io(io::Error::other(message)));
+    }
 
-    for skip_brick in &bricks {
-        // Start each attempt with the same brick stack => clone is necessary.
-        let fallen_bricks =  drop_bricks(& mut bricks.clone(), skip_brick);
+    // The ground is the bottom row, at z = 0.
+    let mut background: Grid<Tile> = Grid::filled(width_x + 1 + width_y, height, Tile::new('.', Style::Faint));
+    for x in (0..width_x).chain(width_x + 1..width_x + 1 + width_y) {
+        background[(x, height - 1)] = Tile::new('-', Style::Plain);
+    }
+    for y in 0..height {
+        background[(width_x, y)] = Tile::new(' ', Style::Faint);
+    }
 
-        sum_of_fallen_bricks += fallen_bricks as u32;
-        if fallen_bricks == 0 {
-            safe_bricks += 1;
+    let mut canvases: Vec<Canvas> = Vec::new();
+    for step in (0..=frames.last.min(snapshot.len())).filter(| &step | frames.contains(step)) {
+        let mut bricks = snapshot.clone();
+        let fallen = drop_bricks(&mut bricks[..step], &Vec::new())?;
+
+        // The settled bricks are drawn over the bricks in the air, and the last settled brick over both.
+        let order = (step..bricks.len()).chain(0..step.saturating_sub(1)).chain(step.checked_sub(1));
+        let mut tiles = background.clone();
+        for index in order {
+            let brick = &bricks[index];
+            let style = match index + 1 {
+                n if n == step => Style::Accent,
+                n if n < step => Style::Primary,
+                _ => Style::Plain
+            };
+            for z in brick[2] as usize..=brick[5] as usize {
+                let columns = (brick[0] as usize..=brick[3] as usize)
+                    .chain(width_x + 1 + brick[1] as usize..=width_x + 1 + brick[4] as usize);
+                for x in columns {
+                    tiles[(x, height - 1 - z)] = Tile::new('#', style);
+                }
+            }
         }
+
+        let caption = format!("Step {step}: {step} of {} bricks settled, {fallen} of them fell.", snapshot.len());
+        canvases.push(Canvas::new(tiles, caption));
     }
+    Ok(canvases)
+}
+
+
+/// Drop the bricks of the snapshot to their starting positions.
+fn settle(snapshot: &[Vec<u16>]) -> Result<Vec<Vec<u16>>> {
+    let mut bricks = snapshot.to_vec();
+    drop_bricks(&mut bricks, &Vec::new())?;
+    Ok(bricks)
+}
+
 
-    (safe_bricks, sum_of_fallen_bricks)
+/// The number of bricks that fall when each brick is disintegrated.
+fn fallen_bricks(bricks: &[Vec<u16>]) -> Result<Vec<usize>> {
+    // Start each attempt with the same brick stack => clone is necessary.
+    bricks.iter().enumerate().map(| (index, skip_brick) | {
+        observe::progress("disintegrated bricks", index + 1, Some(bricks.len()));
+        drop_bricks(&mut bricks.to_vec(), skip_brick)
+    })
+    .collect()
+}
+
+
+fn parse_brick(line: Line) -> Result<Vec<u16>> {
+    // Parse a brick "x1,y1,z1~x2,y2,z2" where each coordinate of the end is at least that of the start.
+    let brick: Vec<u16> = line.text
+        .split(&[',', '~'])
+        .map(| c| line.parse::<u16>(c))
+        .collect::<Result<_>>()?;
+
+    if brick.len() != 6 {
+        return Err(line.error(line.text, "expected a brick like \"x1,y1,z1~x2,y2,z2\""));
+    }
+    if brick[0] > brick[3] || brick[1] > brick[4] || brick[2] > brick[5] {
+        return Err(line.error(line.text, "the end of a brick must not be before its start"));
+    }
+    if brick[3] >= GROUND || brick[4] >= GROUND {
+        return Err(line.error(line.text, format!("a brick must be within the ground of {GROUND} by {GROUND}")));
+    }
+    if brick[2] == 0 {
+        return Err(line.error(line.text, "a brick must be above the ground at z = 0"));
+    }
+    Ok(brick)
 }
 
 
-fn drop_bricks(bricks: &mut [Vec<u16>], skip_brick: &Vec<u16>) -> u16 {
+fn drop_bricks(bricks: &mut [Vec<u16>], skip_brick: &Vec<u16>) -> Result<usize> {
     let mut high_points: HashMap<(u16, u16), u16> = HashMap::new();
-    let mut fallen_bricks: u16 = 0;
+    let mut fallen_bricks: usize = 0;
 
     for brick in bricks.iter_mut() {
         if skip_brick == brick {
@@ -51,24 +195,23 @@ fn drop_bricks(bricks: &mut [Vec<u16>], skip_brick: &Vec<u16>) -> u16 {
         let z1 = brick[2];
         let z2  = brick[5];
 
-        // Generate the area of the brick
-        let area: Vec<(u16, u16)> = itertools::iproduct!(
-            brick[0]..=brick[3], brick[1]..=brick[4]
-        ).collect();
+        // The area of the brick
+        let area = || itertools::iproduct!(brick[0]..=brick[3], brick[1]..=brick[4]);
 
-        // Determine the current highest_point in the area
-        let highest_point: u16 = area
-            .iter()
-            .map(| a | high_points.get(a).unwrap_or(&0))
+        // Determine the current highest_point in the area, bricks that overlap in the snapshot can stack too high.
+        let too_high = || Error::unsolvable(format!("the bricks stack higher than z = {}", u16::MAX));
+        let highest_point: u16 = area()
+            .map(| a | *high_points.get(&a).unwrap_or(&0))
             .max()
-            .unwrap() + 1;
+            .unwrap_or(0)
+            .checked_add(1)
+            .ok_or_else(too_high)?;
+        let top = highest_point.checked_add(z2 - z1).ok_or_else(too_high)?;
 
         // Put the brick on top of the highest point.
-        area
-            .iter()
-            .for_each(| a | {
-                high_points.insert(*a, highest_point + z2 - z1);
-            });
+        for a in area() {
+            high_points.insert(a, top);
+        }
 
         // Check if the brick has fallen.
         if z1 > highest_point {
@@ -76,9 +219,38 @@ fn drop_bricks(bricks: &mut [Vec<u16>], skip_brick: &Vec<u16>) -> u16 {
 
             // Update the z-positions if it did fall.
             brick[2] = highest_point;
-            brick[5] = highest_point + z2 - z1;
+            brick[5] = top;
         }
     }
 
-    fallen_bricks
-}
\ No newline at end of file
+    Ok(fallen_bricks)
+}
+
+
+/// Generate a snapshot of falling bricks of up to four cubes in a column of 10 by 10.
+/// The size is the number of bricks, at most 65535.
+/// Every brick is placed above the bricks below it with some space in between, so that no bricks overlap.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut heights: [[usize; 10]; 10] = [[0; 10]; 10];
+    let mut bricks: Vec<String> = Vec::new();
+
+    for _ in 0..size.min(u16::MAX as usize) {
+        let mut length = [0; 3];
+        length[rng.range(0..3)] = rng.range(0..4);
+        let (x, y) = (rng.range(0..10 - length[0]), rng.range(0..10 - length[1]));
+
+        let below = (x..=x + length[0])
+            .flat_map(| x | (y..=y + length[1]).map(move | y | (x, y)))
+            .map(| (x, y) | heights[x][y])
+            .max()
+            .unwrap();
+        let z = below + 1 + rng.range(0..4);
+        for column in &mut heights[x..=x + length[0]] {
+            column[y..=y + length[1]].fill(z + length[2]);
+        }
+
+        bricks.push(format!("{x},{y},{z}~{},{},{}\n", x + length[0], y + length[1], z + length[2]));
+    }
+    rng.shuffle(&mut bricks);
+    bricks.concat()
+}
@@ -1,42 +1,105 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
-const  MAX_RED: u8 = 12;
-const MAX_GREEN: u8 = 13;
-const MAX_BLUE: u8 = 14;
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
+
+const  MAX_RED: u32 = 12;
+const MAX_GREEN: u32 = 13;
+const MAX_BLUE: u32 = 14;
+
+pub struct CubeConundrum;
+
+impl Solver for CubeConundrum {
+    fn day(&self) -> u8 { 2 }
+
+    fn title(&self) -> &'static str { "Cube Conundrum" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Possible Games", "Cube Power"] }
+
+    fn input_file(&self) -> &'static str { "day_2_cube_conundrum.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_games_and_power(file_path: &str) -> Result<(u32, u32)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u32, u32)> {
+    let games = parse(data)?;
+    Ok((part_one(&games)?, part_two(&games)?))
+}
+
+/// Parse the games into their number and draws.
+pub fn parse(data: &str) -> Result<Vec<Game<'_>>> {
+    util::lines(data)
+        .map(| line | {
+            let (game, draws) = line.split_once(line.text, ":")?;
+            let game = game
+                .strip_prefix("Game ")
+                .ok_or_else(|| line.error(game, "expected \"Game <number>\""))?;
+
+            let draws: Vec<Vec<Cubes>> = draws
+                .split(';')
+                .map(| draw | parse_draw(&line, draw))
+                .collect::<Result<_>>()?;
+
+            Ok((line.parse::<u32>(game)?, draws))
+        })
+        .collect()
+}
 
 /// Calculate the sum of game numbers for valid games
 /// A game is valid if all draws have all cubes below the max count
-pub fn determine_games_and_power(file_path: &str) -> (u32, u32) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+pub fn part_one(games: &[Game]) -> Result<u32> {
+    Ok(games
+        .iter()
+        .filter(| (_, draws) | draws.iter().all(| draw | valid_draw(draw)))
+        .map(| (game, _) | game)
+        .sum())
+}
 
-    let mut valid_game_sum: u32 = 0;
-    let mut cube_power_sum: u32 = 0;
+/// Calculate the sum of the cube power of all games.
+pub fn part_two(games: &[Game]) -> Result<u32> {
+    Ok(games.iter().map(| (_, draws) | cube_power(draws)).sum())
+}
 
-    for row in data.lines() {
-        let (game, draws) = row.split_once(':').unwrap();
+/// Game number and the cubes of each draw.
+pub type Game<'a> = (u32, Vec<Vec<Cubes<'a>>>);
 
-        if draws.split(';').all(valid_draw) {
-            valid_game_sum += game[5..].parse::<u32>().unwrap();  // game = "Game xx" so 5.. for slice
-        }
+/// Number of cubes and their color.
+pub type Cubes<'a> = (u32, &'a str);
 
-        cube_power_sum += cube_power(draws);
-    }
+/// Parse a draw such as "3 blue, 4 red" into the cubes it contains.
+fn parse_draw<'a>(line: &Line<'a>, draw: &'a str) -> Result<Vec<Cubes<'a>>> {
+    let mut cubes: Vec<Cubes> = Vec::new();
 
-    (valid_game_sum, cube_power_sum)
+    for item in draw.split(',') {
+        let (count, color) = line.split_once(item.trim(), " ")?;
+
+        if !matches!(color, "red" | "green" | "blue") {
+            return Err(line.error(color, format!("unknown color {color:?}")));
+        }
+        cubes.push((line.parse::<u32>(count)?, color));
+    }
+    Ok(cubes)
 }
 
 /// Determine if a draw is valid. Return immediately upon finding an invalid draw.
 #[inline]
-fn valid_draw(draw: &str) -> bool {
-    
-    for cubes in draw.split(',') {
-        let (count, color) = cubes
-            .trim()
-            .split_once(' ')
-            .unwrap();
-
-        let count: u8 = count.parse::<u8>().unwrap();
-
+fn valid_draw(draw: &[Cubes]) -> bool {
+    for &(count, color) in draw {
         match color {
             "red" if count > MAX_RED => return false,
             "green" if count > MAX_GREEN => return false,
@@ -49,16 +112,13 @@ fn valid_draw(draw: &str) -> bool {
 
 /// Determine the cube power.
 #[inline]
-fn cube_power(game: &str) -> u32 {
+fn cube_power(game: &[Vec<Cubes>]) -> u32 {
     let mut min_red: u32 = 0;
     let mut min_green: u32 = 0;
     let mut min_blue: u32 = 0;
 
-    for draw in game.split(';') {
-        for cubes in draw.split(',') {
-            let (count, color) = cubes.trim().split_once(' ').unwrap();
-            let count: u32 = count.parse::<u32>().unwrap();
-
+    for draw in game {
+        for &(count, color) in draw {
             match color {
                 "red" if count > min_red => min_red = count,
                 "green" if count > min_green => min_green = count,
@@ -71,6 +131,30 @@ fn cube_power(game: &str) -> u32 {
 }
 
 
+/// Generate games of one to six draws of up to twenty cubes of each color.
+/// The size is the number of games.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut data = String::new();
+
+    for game in 1..=size {
+        let draws: Vec<String> = (0..rng.range(1..7))
+            .map(| _ | {
+                let mut colors = ["red", "green", "blue"];
+                rng.shuffle(&mut colors);
+                let count = rng.range(1..4);
+                colors[..count]
+                    .iter()
+                    .map(| color | format!("{} {color}", rng.range(1..21)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .collect();
+        data += &format!("Game {game}: {}\n", draws.join("; "));
+    }
+    data
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,17 +169,28 @@ mod tests {
     const POSSIBLE_GAMES: [bool; 5] = [true, true, false, false, true];
     const CUBE_POWER: [u32; 5] = [48, 12, 1560, 630, 36];
 
+    fn parse_game(game: &str) -> Vec<Vec<Cubes<'_>>> {
+        let line = Line { index: 0, text: game };
+        game.split(';').map(| draw | parse_draw(&line, draw).unwrap()).collect()
+    }
+
     #[test]
     fn test_valid_draw() {
         for (input, expected) in EXAMPLE.iter().zip(POSSIBLE_GAMES) {
-            assert_eq!(input.split(';').all(valid_draw), expected);
+            assert_eq!(parse_game(input).iter().all(| draw | valid_draw(draw)), expected);
         }
     }
 
     #[test]
     fn test_cube_power() {
         for (input, expected) in EXAMPLE.iter().zip(CUBE_POWER) {
-            assert_eq!(cube_power(input), expected);
+            assert_eq!(cube_power(&parse_game(input)), expected);
         }
     }
+
+    #[test]
+    fn test_unknown_color() {
+        let error = solve("Game 1: 3 blue, 4 purple").unwrap_err();
+        assert_eq!(error.to_string(), "line 1, column 19: unknown color \"purple\"");
+    }
 }
\ No newline at end of file
@@ -1,44 +1,98 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
 use regex::Regex;
 
-pub fn determine_part_number_and_gear_ratio(file_path: &str) -> (u32, u32) {
-    // For each number check if it is adjacent a punctuation character
-    // If true add to the total.
-    let data: String = fs::read_to_string(file_path)
-        .unwrap()
-        .replace('.', " ");  // Remove periods so that all punctuation can be matched.
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-    let mut data = data.lines().peekable();
-    let re: Regex = Regex::new(r"\d+").unwrap();
+pub struct GearRatios;
 
-    let mut number_total: u32 = 0;
-    let mut gear_ratio: u32 = 0;
-    let mut previous_line: &str = "";
+impl Solver for GearRatios {
+    fn day(&self) -> u8 { 3 }
 
-    while let Some(current_line) = data.next() {
+    fn title(&self) -> &'static str { "Gear Ratios" }
 
-        let next_line: &str = match data.peek() {
-            Some(line) => line,
-            None => ""  // Next line is beyond end of file
-        };
+    fn labels(&self) -> [&'static str; 2] { ["Part Number", "Gear Ratio"] }
 
-        for number in re.find_iter(current_line) {
-            if next_to_punctuation(current_line, number.start(), number.end())
+    fn input_file(&self) -> &'static str { "day_3_gear_ratios.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_part_number_and_gear_ratio(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let schematic = parse(data)?;
+    Ok((part_one(&schematic)?, part_two(&schematic)?))
+}
+
+pub fn parse(data: &str) -> Result<String> {
+    Ok(data.replace('.', " "))  // Remove periods so that all punctuation can be matched.
+}
+
+/// For each number check if it is adjacent a punctuation character
+/// If true add to the total.
+pub fn part_one(schematic: &str) -> Result<u64> {
+    let re: Regex = Regex::new(r"[0-9]+").unwrap();
+    let mut number_total: u64 = 0;
+
+    for (previous_line, line, next_line) in with_neighbours(schematic) {
+        for number in re.find_iter(line.text) {
+            if next_to_punctuation(line.text, number.start(), number.end())
                 || search_line_for_punctuation(previous_line, number.start(), number.end())
                 || search_line_for_punctuation(next_line, number.start(), number.end()) {
 
-                number_total += number.as_str().parse::<u32>().unwrap();
+                number_total = number_total
+                    .checked_add(line.parse::<u64>(number.as_str())?)
+                    .ok_or_else(|| Error::unsolvable("the sum of the part numbers does not fit a u64"))?;
             }
         }
+    }
+    Ok(number_total)
+}
 
-        for (index, _gear_char) in current_line.match_indices('*') {
-            gear_ratio += calculate_ratio(previous_line, current_line, next_line, &re, index);
-        }
+/// Sum the ratios of all gears.
+pub fn part_two(schematic: &str) -> Result<u64> {
+    let re: Regex = Regex::new(r"[0-9]+").unwrap();
+    let mut gear_ratio: u64 = 0;
 
-        previous_line = current_line;
+    for (previous_line, line, next_line) in with_neighbours(schematic) {
+        for (index, _gear_char) in line.text.match_indices('*') {
+            gear_ratio = calculate_ratio(previous_line, line.text, next_line, &re, index)
+                .map_err(| part | line.error(&line.text[index..], format!("invalid number {part:?} next to gear")))?
+                .and_then(| ratio | gear_ratio.checked_add(ratio))
+                .ok_or_else(|| Error::unsolvable("the sum of the gear ratios does not fit a u64"))?;
+        }
     }
-    (number_total, gear_ratio)
+    Ok(gear_ratio)
+}
+
+
+/// Each line together with the text of the previous and next line.
+/// Lines beyond the start or end of the schematic are empty.
+fn with_neighbours(schematic: &str) -> Vec<(&str, Line<'_>, &str)> {
+    let lines: Vec<Line> = util::lines(schematic).collect();
+    let text = | index: Option<usize> | index
+        .and_then(| index | lines.get(index))
+        .map_or("", | line | line.text);
+
+    lines
+        .iter()
+        .map(| line | (text(line.index.checked_sub(1)), *line, text(Some(line.index + 1))))
+        .collect()
 }
 
 
@@ -79,29 +133,88 @@ fn calculate_ratio(
     next_line: &str,
     regex: &Regex,
     index: usize
-) -> u32 {
-    // For a gear at "index" calculate the ratio of the surrounding numbers
-    let mut adjacent_numbers: Vec<u32> = Vec::new();
-    find_adjacent(&mut adjacent_numbers, previous_line, regex, index);
-    find_adjacent(&mut adjacent_numbers, current_line, regex, index);
-    find_adjacent(&mut adjacent_numbers, next_line, regex, index);
+) -> std::result::Result<Option<u64>, String> {
+    // For a gear at "index" calculate the ratio of the surrounding numbers, None if it does not fit a u64.
+    // On failure the number that could not be parsed is returned.
+    let mut adjacent_numbers: Vec<u64> = Vec::new();
+    find_adjacent(&mut adjacent_numbers, previous_line, regex, index)?;
+    find_adjacent(&mut adjacent_numbers, current_line, regex, index)?;
+    find_adjacent(&mut adjacent_numbers, next_line, regex, index)?;
 
 
     if adjacent_numbers.len() > 1 {
-        adjacent_numbers.iter().product()
+        Ok(adjacent_numbers.iter().try_fold(1_u64, | product, &number | product.checked_mul(number)))
     } else {
-        0
+        Ok(Some(0))
     }
 }
 
 
 #[inline]
-fn find_adjacent(adjacent_numbers: &mut Vec<u32>, line: &str, regex: &Regex, gear_index: usize){
+fn find_adjacent(
+    adjacent_numbers: &mut Vec<u64>, line: &str, regex: &Regex, gear_index: usize
+) -> std::result::Result<(), String> {
     for number in regex.find_iter(line) {
         if number.start().saturating_sub(1) <= gear_index && gear_index <= number.end() {
-            adjacent_numbers.push(number.as_str().parse::<u32>().unwrap());
+            let value = number.as_str().parse::<u64>().map_err(| _ | number.as_str().to_string())?;
+            adjacent_numbers.push(value);
+        }
+    }
+    Ok(())
+}
+
+
+/// Generate a square schematic of numbers with up to three digits and symbols around them.
+/// The size is the width and height of the schematic.
+/// A gear is next to at most two numbers, and the gear ratios are only added while their sum fits the answer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut schematic: Vec<Vec<u8>> = vec![vec![b'.'; size]; size];
+    let mut numbers: Vec<u32> = Vec::new();
+    // Index of the number that covers each position.
+    let mut covered_by: Vec<Vec<Option<usize>>> = vec![vec![None; size]; size];
+
+    for y in 0..size {
+        let mut x = rng.range(0..4);
+        while x < size {
+            let digits = rng.range(1..4).min(size - x) as u32;
+            let number = rng.range(10_usize.pow(digits - 1)..10_usize.pow(digits));
+            for (i, digit) in number.to_string().bytes().enumerate() {
+                schematic[y][x + i] = digit;
+                covered_by[y][x + i] = Some(numbers.len());
+            }
+            numbers.push(number as u32);
+            x += digits as usize + rng.range(2..8);
+        }
+    }
+
+    let mut gear_ratios: u32 = 0;
+    for (x, y) in (0..size).flat_map(| y | (0..size).map(move | x | (x, y))) {
+        if schematic[y][x] != b'.' || !rng.chance(0.1) {
+            continue;
+        }
+
+        let mut adjacent: Vec<usize> = (y.saturating_sub(1)..(y + 2).min(size))
+            .flat_map(| y | (x.saturating_sub(1)..(x + 2).min(size)).map(move | x | (x, y)))
+            .filter_map(| (x, y) | covered_by[y][x])
+            .collect();
+        adjacent.dedup();
+
+        let mut symbol = rng.pick_char("*#+$/=%@&-") as u8;
+        if symbol == b'*' {
+            let ratio = match adjacent[..] {
+                [first, second] => gear_ratios.checked_add(numbers[first] * numbers[second]),
+                [_, _, _, ..] => None,
+                _ => Some(gear_ratios)
+            };
+            match ratio {
+                Some(total) => gear_ratios = total,
+                None => symbol = b'#'
+            }
         }
+        schematic[y][x] = symbol;
     }
+
+    schematic.into_iter().map(| row | String::from_utf8(row).unwrap() + "\n").collect()
 }
 
 
@@ -118,20 +231,34 @@ mod tests {
             "...*......",
             "..35..633.",
             &re, 3);
-        assert_eq!(gear_ratio, 16345);
+        assert_eq!(gear_ratio, Ok(Some(16345)));
 
         let gear_ratio = calculate_ratio(
             "......#...",
             "617*......",
             ".....+.58.",
             &re, 3);
-        assert_eq!(gear_ratio, 0);
+        assert_eq!(gear_ratio, Ok(Some(0)));
 
         let gear_ratio = calculate_ratio(
             "......755.",
             "...$.*....",
             ".664.598..",
             &re, 5);
-        assert_eq!(gear_ratio, 451490);
+        assert_eq!(gear_ratio, Ok(Some(451490)));
+
+        let gear_ratio = calculate_ratio(
+            "4294967295",
+            "*.........",
+            "4294967295",
+            &re, 0);
+        assert_eq!(gear_ratio, Ok(Some(18446744065119617025)));
+
+        let gear_ratio = calculate_ratio(
+            "4294967295",
+            "*4294967295",
+            "4294967295",
+            &re, 0);
+        assert_eq!(gear_ratio, Ok(None));
     }
 }
@@ -1,39 +1,117 @@
 use std::collections::HashSet;
+#[cfg(feature = "fs")]
 use std::fs;
 
-pub fn determine_scratchcards_and_points(file_path: &str) -> (u32, u32) {
-    let data: String = fs::read_to_string(file_path).unwrap();
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-    let mut total_points: u32 = 0;
-    let mut card_count: Vec<u32> = vec![1; data.lines().count()];
+pub struct Scratchcards;
 
-    for (row, line) in data.lines().enumerate() {
-        let (winning_numbers, our_numbers) = line.split_once('|').unwrap();
-        let (_game, winning_numbers) = winning_numbers.split_once(':').unwrap();
+impl Solver for Scratchcards {
+    fn day(&self) -> u8 { 4 }
 
-        let winning_numbers: HashSet<u32> = string_to_num_set(winning_numbers);
-        let our_numbers: HashSet<u32> = string_to_num_set(our_numbers);
+    fn title(&self) -> &'static str { "Scratchcards" }
 
-        let wins: usize = our_numbers.intersection(&winning_numbers).count();
+    fn labels(&self) -> [&'static str; 2] { ["Scratchcard Points", "Scratchcard Total"] }
 
-        if wins > 0 {
-            total_points += 1 << (wins - 1);
+    fn input_file(&self) -> &'static str { "day_4_scratchcards.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_scratchcards_and_points(file_path: &str) -> Result<(u32, u32)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u32, u32)> {
+    let wins = parse(data)?;
+    Ok((part_one(&wins)?, part_two(&wins)?))
+}
+
+/// Parse the cards into the number of winning numbers on each card.
+pub fn parse(data: &str) -> Result<Vec<usize>> {
+    util::lines(data)
+        .map(| line | {
+            let (winning_numbers, our_numbers) = line.split_once(line.text, "|")?;
+            let (_game, winning_numbers) = line.split_once(winning_numbers, ":")?;
+
+            let winning_numbers: HashSet<u32> = string_to_num_set(&line, winning_numbers)?;
+            let our_numbers: HashSet<u32> = string_to_num_set(&line, our_numbers)?;
+
+            Ok(our_numbers.intersection(&winning_numbers).count())
+        })
+        .collect()
+}
+
+pub fn part_one(wins: &[usize]) -> Result<u32> {
+    Ok(wins
+        .iter()
+        .filter(| &&wins | wins > 0)
+        .map(| wins | 1 << (wins - 1))
+        .sum())
+}
+
+pub fn part_two(wins: &[usize]) -> Result<u32> {
+    let mut card_count: Vec<u32> = vec![1; wins.len()];
+
+    for (row, &wins) in wins.iter().enumerate() {
+        if row + wins >= card_count.len() {
+            return Err(Error::unsolvable(format!("card {} wins cards past the end of the table", row + 1)));
         }
 
         for i in 1..wins + 1 {
             card_count[row + i] += card_count[row]
         }
     }
-    (total_points, card_count.into_iter().sum())
+    Ok(card_count.into_iter().sum())
 }
 
 
 #[inline]
-fn string_to_num_set(line: &str) -> HashSet<u32> {
+fn string_to_num_set(line: &Line, numbers: &str) -> Result<HashSet<u32>> {
     // Parse a line of numbers (separated by whitespace) to a Hashset of the values.
-    HashSet::from_iter(
-        line
-            .split_whitespace()
-            .map(| num | num.trim().parse::<u32>().unwrap())
-    )
-}
\ No newline at end of file
+    numbers
+        .split_whitespace()
+        .map(| num | line.parse::<u32>(num))
+        .collect()
+}
+
+
+/// Generate cards of ten winning numbers and twenty-five numbers we have.
+/// The size is the number of cards.
+/// No card wins copies past the end of the table, and the total number of cards fits the answer.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    // Keeping the copies of each card below the limit keeps the total below u32::MAX.
+    let limit = u32::MAX as u64 / size.max(1) as u64;
+    let mut copies: Vec<u64> = vec![1; size];
+    let mut data = String::new();
+
+    for card in 0..size {
+        let wins = (0..=rng.range(0..11).min(size - card - 1))
+            .rev()
+            .find(| &wins | copies[card + 1..=card + wins].iter().all(| &count | count + copies[card] <= limit))
+            .unwrap_or(0);
+        for next in card + 1..=card + wins {
+            copies[next] += copies[card];
+        }
+
+        let mut numbers: Vec<u32> = (1..100).collect();
+        rng.shuffle(&mut numbers);
+        let mut ours: Vec<u32> = numbers[..wins].iter().chain(&numbers[10..35 - wins]).copied().collect();
+        rng.shuffle(&mut ours);
+
+        let format = | numbers: &[u32] | numbers.iter().map(| n | format!("{n:>2}")).collect::<Vec<_>>().join(" ");
+        data += &format!("Card {:>3}: {} | {}\n", card + 1, format(&numbers[..10]), format(&ours));
+    }
+    data
+}
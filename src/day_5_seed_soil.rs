@@ -1,4 +1,11 @@
+#[cfg(feature = "fs")]
 use std::fs;
+use std::ops::Range;
+
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
 struct MapTraversal {
     groups: Vec<MapGroup>
@@ -9,176 +16,261 @@ impl MapTraversal {
         Self { groups }
     }
 
-    fn find_location(&self, seed: usize) -> usize {
+    fn find_location(&self, seed: u64) -> u64 {
         self.groups.iter().fold(seed, | map, group | group.find_range(map))
     }
 
-    fn find_source(&self, destination: usize, group_idx: usize) -> usize {
-        let mut value = destination;
-        for map in self.groups.iter().take(group_idx).rev() {
-            value = map.reverse_find(value);
-        }
-        value
-    }
-
-    fn find_from_specified_level(&self, source: usize, group_idx: usize) -> usize {
-        self.groups[group_idx..]
-            .iter()
-            .fold(source, | map, group | group.find_range(map))
-    }
-
-    fn levels(&self) -> usize {
-        self.groups.len()
-    }
-
-    fn boundaries(&self, group_idx: usize) -> impl Iterator<Item = usize> + '_ {
-        self.groups[group_idx].boundaries()
+    fn find_location_ranges(&self, seeds: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        self.groups.iter().fold(seeds, | ranges, group | group.map_ranges(ranges))
     }
 }
 
 #[derive(Debug)]
 struct MapGroup {
-    maps: Vec<MapItem>
+    maps: Vec<MapItem>,
+    boundaries: Vec<u64>
 }
 
 impl MapGroup {
     fn new(mut items: Vec<MapItem>) -> Self {
         items.sort_by_key(| item | item.source);
-        Self { maps: items }
+        let mut boundaries: Vec<u64> = items
+            .iter()
+            .flat_map(| item | [item.source, item.source + item.length])
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+        Self { maps: items, boundaries }
     }
 
-    fn find_range(&self, source: usize) -> usize {
+    fn find_range(&self, source: u64) -> u64 {
         match self.maps.iter().find(| item | item.contains_source(source)) {
             Some(item) => item.map(source),
             None => source
         }
     }
 
-    fn reverse_find(&self, destination: usize) -> usize {
-        match self.maps.iter().find(| item | item.contains_destination(destination)) {
-            Some(item) => item.reverse_map(destination),
-            None => destination
+    fn map_ranges(&self, ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+        // Split the ranges at the boundaries of the items, so that every value of a piece is mapped by the same item.
+        let mut mapped: Vec<Range<u64>> = Vec::new();
+        for range in ranges {
+            let mut start = range.start;
+            while start < range.end {
+                let end = self.next_boundary(start).min(range.end);
+                mapped.push(self.find_range(start)..self.find_range(start) + (end - start));
+                start = end;
+            }
         }
+        merge_ranges(mapped)
+    }
+
+    fn next_boundary(&self, value: u64) -> u64 {
+        // The first start or end of an item after the value.
+        let index = self.boundaries.partition_point(| &boundary | boundary <= value);
+        self.boundaries.get(index).copied().unwrap_or(u64::MAX)
     }
+}
+
 
-    fn boundaries(&self) -> impl Iterator<Item = usize> + '_ {
-        std::iter::once(0)
-            .chain(
-                self.maps
-                    .iter()
-                    .flat_map(| item | [item.source, item.source + item.length])
-            )
-            .chain(std::iter::once(usize::MAX))
+/// Sort the ranges and join the ones that overlap or touch, so that no value is mapped twice.
+fn merge_ranges(mut ranges: Vec<Range<u64>>) -> Vec<Range<u64>> {
+    ranges.sort_unstable_by_key(| range | range.start);
+
+    let mut merged: Vec<Range<u64>> = Vec::new();
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range)
+        }
     }
+    merged
 }
 
+
 #[derive(Debug, Clone)]
 struct MapItem {
-    destination: usize,
-    source: usize,
-    length: usize
+    destination: u64,
+    source: u64,
+    length: u64
 }
 
 impl MapItem {
     #[inline]
-    fn contains_source(&self, source: usize) -> bool {
+    fn contains_source(&self, source: u64) -> bool {
         // Checks if the source value is within the range of this item.
         self.source <= source && source < self.source + self.length
     }
 
     #[inline]
-    fn contains_destination(&self, destination: usize) -> bool {
-        // Checks if the destination value is within the range of this item.
-        self.destination <= destination && destination < self.destination + self.length
-    }
-
-    #[inline]
-    fn map(&self, source: usize) -> usize {
+    fn map(&self, source: u64) -> u64 {
         // Map a source value to a destination.
         self.destination + source - self.source
     }
+}
 
-    #[inline]
-    fn reverse_map(&self, destination: usize) -> usize {
-        // Map  a destination to a source.
-        self.source + destination - self.destination
+impl TryFrom<Line<'_>> for MapItem {
+    type Error = Error;
+
+    fn try_from(line: Line) -> Result<Self> {
+        let numbers: Vec<&str> = line.text.split_whitespace().collect();
+        let items: Vec<u64> = numbers
+            .iter()
+            .map(| num | line.parse::<u64>(num))
+            .collect::<Result<_>>()?;
+
+        match items[..] {
+            [destination, source, length] => {
+                // The end of both ranges, including the boundary after the sources, must fit a u64.
+                if source.checked_add(length).is_none() || destination.checked_add(length).is_none() {
+                    return Err(line.error(numbers[2], "the range of the map item does not fit a u64"));
+                }
+                Ok(Self { destination, source, length })
+            },
+            _ => Err(line.error(line.text, "expected \"<destination> <source> <length>\""))
+        }
     }
 }
 
-impl From<&str> for MapItem {
-    fn from(value: &str) -> Self {
-        let items: Vec<usize> = value
-            .split_whitespace()
-            .map(| num | num.parse::<usize>().unwrap())
-            .collect();
 
-        Self { destination: items[0], source: items[1], length: items[2] }
+pub struct SeedToLocation;
+
+impl Solver for SeedToLocation {
+    fn day(&self) -> u8 { 5 }
+
+    fn title(&self) -> &'static str { "Seed-to-Location" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Closest Location 1", "Closest Location 2"] }
+
+    fn input_file(&self) -> &'static str { "day_5_seed_to_location.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
 }
 
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_closest_location(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
 
-pub fn determine_closest_location(file_path: &str) -> (usize, usize) {
-    let data = fs::read_to_string(file_path).unwrap();
-    let mut groups = data.split("\n\n");
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let almanac = parse(data)?;
+    Ok((part_one(&almanac)?, part_two(&almanac)?))
+}
+
+/// The seeds to plant and the maps from seed to location.
+pub struct Almanac {
+    seeds: Vec<u64>,
+    map_traversal: MapTraversal
+}
+
+pub fn parse(data: &str) -> Result<Almanac> {
+    let mut lines = util::lines(data);
 
     // Parse the list of seeds
-    let seeds: Vec<usize> = groups
-        .next()
-        .unwrap()
-        [7..]  // remove "seeds: "
+    let seeds_line = lines.next().ok_or_else(|| Error::parse(0, 0, "expected a list of seeds"))?;
+    let seed_numbers: Vec<&str> = seeds_line.text
+        .strip_prefix("seeds:")
+        .ok_or_else(|| seeds_line.error(seeds_line.text, "expected \"seeds:\""))?
         .split_whitespace()
-        .map(| num | num.parse::<usize>().unwrap())
         .collect();
+    let seeds: Vec<u64> = seed_numbers
+        .iter()
+        .map(| num | seeds_line.parse::<u64>(num))
+        .collect::<Result<_>>()?;
 
-    // Create the seed ranges for part 2
-    let seed_ranges: Vec<std::ops::Range<usize>> = seeds
-        .windows(2)
-        .step_by(2)
-        .map(| x | x[0]..(x[0] + x[1]))
-        .collect();
+    // Each pair of numbers is a seed range in part two, so the end of the range must fit a u64.
+    if let Some(index) = seeds.chunks_exact(2).position(| x | x[0].checked_add(x[1]).is_none()) {
+        return Err(seeds_line.error(seed_numbers[2 * index + 1], "the seed range does not fit a u64"));
+    }
 
     // Create the MapTraversal object that contains the groups and items used to traverse the map.
-    let mut map_traversal: Vec<MapGroup> = Vec::new();
-    for group in groups {
-        let mut map_group: Vec<MapItem> = Vec::new();
-
-        for line in group.lines().skip(1) {
-            map_group.push(MapItem::from(line));
+    // Each group starts with a header line, e.g. "seed-to-soil map:".
+    let mut map_groups: Vec<Vec<MapItem>> = Vec::new();
+    for line in lines {
+        match map_groups.last_mut() {
+            _ if line.text.is_empty() => continue,
+            _ if line.text.ends_with(':') => map_groups.push(Vec::new()),
+            Some(map_group) => map_group.push(MapItem::try_from(line)?),
+            None => return Err(line.error(line.text, "expected a map header"))
         }
-        map_traversal.push(MapGroup::new(map_group));
     }
-    let map_traversal = MapTraversal::new(map_traversal);
+    let map_traversal = MapTraversal::new(map_groups.into_iter().map(MapGroup::new).collect());
 
-    // Traverse through the map to find the closest location.
-    let closest_location_part_one: usize = seeds
-        .into_iter()
-        .map(| seed | map_traversal.find_location(seed))
-        .min()
-        .unwrap();
+    Ok(Almanac { seeds, map_traversal })
+}
 
-    // Traverse the map using every range start
-    // Then traverse along the boundary conditions of the ranges
-    // Retain only the lowest value.
-    let mut closest_location_part_two: usize = seed_ranges
+/// Traverse through the map to find the closest location.
+pub fn part_one(almanac: &Almanac) -> Result<u64> {
+    almanac.seeds
         .iter()
-        .map(| range | range.start)
-        .map(| seed | map_traversal.find_location(seed))
+        .map(| &seed | almanac.map_traversal.find_location(seed))
         .min()
-        .unwrap();
+        .ok_or_else(|| Error::unsolvable("there are no seeds"))
+}
+
+/// Traverse the map with the seed ranges, split into the pieces that each item maps as a whole.
+/// The closest location is the start of the first location range.
+pub fn part_two(almanac: &Almanac) -> Result<u64> {
+    // Create the seed ranges for part 2
+    let seed_ranges: Vec<Range<u64>> = almanac.seeds
+        .chunks_exact(2)
+        .map(| x | x[0]..(x[0] + x[1]))
+        .collect();
 
-    for level in 0..map_traversal.levels() {
-        let alternate_route = map_traversal
-            .boundaries(level)
-            .filter(| &value | {
-                let source = map_traversal.find_source(value, level);
-                seed_ranges.iter().any(| range | range.contains(&source))
-            })
-            .map(| start | map_traversal.find_from_specified_level(start, level))
-            .min()
-            .unwrap();
+    almanac.map_traversal
+        .find_location_ranges(merge_ranges(seed_ranges))
+        .first()
+        .map(| range | range.start)
+        .ok_or_else(|| Error::unsolvable("there are no seeds in the seed ranges"))
+}
 
-        closest_location_part_two = closest_location_part_two.min(alternate_route)
-    }
 
-    (closest_location_part_one, closest_location_part_two)
-}
\ No newline at end of file
+/// Generate an almanac of five seed ranges and seven maps of values below 2^32.
+/// The size is the number of items in each map, the sources and the destinations of a map do not overlap.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const VALUES: u64 = 1 << 32;
+    const CATEGORIES: [&str; 8] = ["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"];
+
+    let seeds: Vec<String> = (0..5)
+        .map(| _ | {
+            let start = rng.range_u64(0..VALUES / 2);
+            format!("{start} {}", rng.range_u64(1..VALUES / 16))
+        })
+        .collect();
+    let mut data = format!("seeds: {}\n", seeds.join(" "));
+
+    // Each item takes at most two spacings, including the gap before it.
+    let spacing = (VALUES / (2 * size.max(1) as u64)).max(2);
+    for categories in CATEGORIES.windows(2) {
+        data += &format!("\n{}-to-{} map:\n", categories[0], categories[1]);
+
+        let lengths: Vec<u64> = (0..size).map(| _ | rng.range_u64(1..spacing)).collect();
+        let mut sources: Vec<u64> = Vec::new();
+        let mut position: u64 = 0;
+        for length in &lengths {
+            sources.push(position + rng.range_u64(0..spacing));
+            position = sources[sources.len() - 1] + length;
+        }
+
+        // The destinations are laid out in a shuffled order of the items.
+        let mut order: Vec<usize> = (0..size).collect();
+        rng.shuffle(&mut order);
+        let mut destinations: Vec<u64> = vec![0; size];
+        let mut position: u64 = 0;
+        for &index in &order {
+            destinations[index] = position + rng.range_u64(0..spacing);
+            position = destinations[index] + lengths[index];
+        }
+
+        for index in order {
+            data += &format!("{} {} {}\n", destinations[index], sources[index], lengths[index]);
+        }
+    }
+    data
+}
@@ -1,40 +1,95 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
-pub fn determine_winning_races(file_path: &str) -> (u32, u32) {
-    // Determine the number of ways to win the boat race.
-    // part one and two use the same calculation, but different inputs.
-    let data = fs::read_to_string(file_path).unwrap();
-    let (times, distances) = data.split_once('\n').unwrap();
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-    let (times, time_part_two) = parse_line(times);
-    let (distances, distance_part_two) = parse_line(distances);
+pub struct WaitForIt;
 
-    let ways_to_win: u32 = times
+impl Solver for WaitForIt {
+    fn day(&self) -> u8 { 6 }
+
+    fn title(&self) -> &'static str { "Wait for it (Boat Race)" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Ways to Win 1", "Ways to Win 2"] }
+
+    fn input_file(&self) -> &'static str { "day_6_boat_race.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_winning_races(file_path: &str) -> Result<(u32, u32)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(u32, u32)> {
+    let races = parse(data)?;
+    Ok((part_one(&races)?, part_two(&races)?))
+}
+
+/// Times and record distances of the boat races.
+/// Part two reads each line as a single race, ignoring the spaces between the numbers.
+pub struct Races {
+    times: Vec<f64>,
+    distances: Vec<f64>,
+    time_part_two: f64,
+    distance_part_two: f64
+}
+
+pub fn parse(data: &str) -> Result<Races> {
+    let mut lines = util::lines(data);
+    let (Some(times), Some(distances)) = (lines.next(), lines.next()) else {
+        return Err(Error::parse(0, 0, "expected a line of times and a line of distances"));
+    };
+
+    let (times, time_part_two) = parse_line(&times)?;
+    let (distances, distance_part_two) = parse_line(&distances)?;
+
+    Ok(Races { times, distances, time_part_two, distance_part_two })
+}
+
+// Determine the number of ways to win the boat race.
+// part one and two use the same calculation, but different inputs.
+
+pub fn part_one(races: &Races) -> Result<u32> {
+    Ok(races.times
         .iter()
-        .zip(distances)
-        .fold(1, | acc, (time, distance) | acc * calculate_ways_to_win(time, &distance));
+        .zip(&races.distances)
+        .fold(1, | acc, (time, distance) | acc * calculate_ways_to_win(time, distance)))
+}
 
-    (ways_to_win, calculate_ways_to_win(&time_part_two, &distance_part_two))
+pub fn part_two(races: &Races) -> Result<u32> {
+    Ok(calculate_ways_to_win(&races.time_part_two, &races.distance_part_two))
 }
 
 
 #[inline]
-fn parse_line(line: &str) -> (Vec<f64>, f64) {
+fn parse_line(line: &Line) -> Result<(Vec<f64>, f64)> {
     // Parse a line of the input data to extract the numbers
-    let values_raw = line
+    let values_raw = line.text
         .split_whitespace()
         .skip(1);
 
     // Values as vector
     let values: Vec<f64> = values_raw
         .clone()
-        .map(| num | num.parse::<f64>().unwrap())
-        .collect();
+        .map(| num | line.parse::<f64>(num))
+        .collect::<Result<_>>()?;
 
     // Values concatenated
-    let value_part_two: f64 = String::from_iter(values_raw).parse::<f64>().unwrap();
+    let value_part_two: f64 = line.parse::<f64>(&String::from_iter(values_raw))?;
 
-    (values, value_part_two)
+    Ok((values, value_part_two))
 }
 
 
@@ -49,10 +104,31 @@ fn calculate_ways_to_win(time: &f64, distance: &f64) -> u32 {
 
     let discriminant: f64 = (time * time - 4. * distance).sqrt();
 
-    // Use EPSILON to fix boundary condition when start or end is exactly an integer value.
-    // We only win if we exceed the distance not when we are exactly equal.
-    let interval_start = ((-time + discriminant) / -2. + f64::EPSILON).ceil() as u32;
-    let interval_end = ((-time - discriminant) / -2. - f64::EPSILON).floor() as u32;
+    // We only win if we exceed the distance not when we are exactly equal,
+    // so the interval starts at the first integer after the lower root and ends before the upper root.
+    let interval_start = ((-time + discriminant) / -2.).floor() as u32 + 1;
+    let interval_end = (((-time - discriminant) / -2.).ceil() as u32).saturating_sub(1);
+
+    (interval_end + 1).saturating_sub(interval_start)
+}
+
+
+/// Generate races with times of two digits.
+/// The size is the number of races, at most four so that the ways to win of part two fit the answer.
+/// Every race, including the race of part two, can be won.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let races = size.clamp(1, 4);
+    loop {
+        let times: Vec<u64> = (0..races).map(| _ | rng.range(10..100) as u64).collect();
+        let distances: Vec<u64> = times.iter().map(| &time | rng.range(time as usize..(time * time / 4) as usize) as u64).collect();
 
-    interval_end.abs_diff(interval_start) + 1
-}
\ No newline at end of file
+        // The best distance is reached by holding the button for half the time.
+        let concatenate = | values: &[u64] | values.iter().map(u64::to_string).collect::<String>();
+        let time: u128 = concatenate(&times).parse().unwrap();
+        let distance: u128 = concatenate(&distances).parse().unwrap();
+        if distance < (time / 2) * (time - time / 2) {
+            let format = | values: &[u64] | values.iter().map(| value | format!("{value:>5}")).collect::<String>();
+            return format!("Time:    {}\nDistance:{}\n", format(&times), format(&distances));
+        }
+    }
+}
@@ -1,46 +1,99 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "fs")]
 use std::fs;
 
 use phf::phf_map;
 
-pub fn determine_total_winnings(file_path: &str) -> (usize, usize) {
-    // To determine the total winnings we must do a few things:
-    // Score each hand
-    // Value each card in the hand (in order)
-    // Sort the hands by strength Score -> Value[card[0]] -> Value[card[1]] -> etc.
-    // Calculate the winnings = sum( bid * rank )
-    let data: String = fs::read_to_string(file_path).unwrap();
+use crate::error::Result;
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, Line};
+use crate::util::random::Rng;
 
-    let mut hands_part_one: Vec<CardStrength> = Vec::new();
-    let mut hands_part_two: Vec<CardStrength> = Vec::new();
+pub struct CamelCards;
 
-    for line in data.lines() {
-        let (hand, bid): (Vec<char>, usize) = match line.split_once(' ') {
-            Some((h, b)) => (h.chars().collect(), b.parse::<usize>().unwrap()),
+impl Solver for CamelCards {
+    fn day(&self) -> u8 { 7 }
+
+    fn title(&self) -> &'static str { "Camel Cards" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Winnings 1", "Winnings 2"] }
+
+    fn input_file(&self) -> &'static str { "day_7_camel_cards.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_total_winnings(file_path: &str) -> Result<(u64, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+// To determine the total winnings we must do a few things:
+// Score each hand
+// Value each card in the hand (in order)
+// Sort the hands by strength Score -> Value[card[0]] -> Value[card[1]] -> etc.
+// Calculate the winnings = sum( bid * rank )
+
+pub fn solve(data: &str) -> Result<(u64, u64)> {
+    let hands = parse(data)?;
+    Ok((part_one(&hands)?, part_two(&hands)?))
+}
+
+/// A hand of cards, the value of each card in part one and two, and the bid.
+pub struct Hand {
+    cards: Vec<char>,
+    values: ([u8; 5], [u8; 5]),
+    bid: usize
+}
+
+pub fn parse(data: &str) -> Result<Vec<Hand>> {
+    let mut hands: Vec<Hand> = Vec::new();
+
+    for line in util::lines(data) {
+        let (hand, bid): (&str, usize) = match line.text.split_once(' ') {
+            Some((h, b)) => (h, line.parse::<usize>(b)?),
             None => continue
         };
 
-        let (score_part_one, score_part_two) = calculate_card_score(&hand);
-        let (hand_values_part_one, hand_values_part_two) = encode_hand(&hand);
-
-        hands_part_one.push(CardStrength::new(score_part_one, hand_values_part_one, bid));
-        hands_part_two.push(CardStrength::new(score_part_two, hand_values_part_two, bid));
+        let values = encode_hand(&line, hand)?;
+        hands.push(Hand { cards: hand.chars().collect(), values, bid });
     }
+    Ok(hands)
+}
+
+pub fn part_one(hands: &[Hand]) -> Result<u64> {
+    let hands: Vec<CardStrength> = hands
+        .iter()
+        .map(| hand | CardStrength::new(calculate_card_score(&hand.cards).0, hand.values.0, hand.bid))
+        .collect();
 
-    hands_part_one.sort();
-    hands_part_two.sort();
+    Ok(total_winnings(hands))
+}
 
-    let winnings_part_one: usize = hands_part_one
-        .into_iter()
-        .enumerate()
-        .fold(0, | acc, (index, hand) | acc + hand.bid * (index + 1));
+pub fn part_two(hands: &[Hand]) -> Result<u64> {
+    let hands: Vec<CardStrength> = hands
+        .iter()
+        .map(| hand | CardStrength::new(calculate_card_score(&hand.cards).1, hand.values.1, hand.bid))
+        .collect();
 
-    let winnings_part_two: usize = hands_part_two
+    Ok(total_winnings(hands))
+}
+
+
+#[inline]
+fn total_winnings(mut hands: Vec<CardStrength>) -> u64 {
+    hands.sort();
+    hands
         .into_iter()
         .enumerate()
-        .fold(0, | acc, (index, hand) | acc + hand.bid * (index + 1));
-
-    (winnings_part_one, winnings_part_two)
+        .fold(0, | acc, (index, hand) | acc + hand.bid as u64 * (index as u64 + 1))
 }
 
 
@@ -104,7 +157,7 @@ impl CardStrength {
 
 
 #[inline]
-fn calculate_card_score(hand_chars: &Vec<char>) -> (u8, u8) {
+fn calculate_card_score(hand_chars: &[char]) -> (u8, u8) {
     // Calculate the card score by counting each character and then converting that into a score.
 
     // Create a map with the count of each character
@@ -169,14 +222,44 @@ fn key_of_max_value<K, V>(hash_map: &HashMap<K, V>) -> Option<&K> where V: Ord,
 
 
 #[inline]
-fn encode_hand(hand_chars: &[char]) -> ([u8; 5], [u8; 5]) {
+fn encode_hand(line: &Line, hand: &str) -> Result<([u8; 5], [u8; 5])> {
     // Encode a hand into the value of each card.
     let mut hand_values_part_one: [u8; 5] = [0; 5];
     let mut hand_values_part_two: [u8; 5] = [0; 5];
 
-    for (i, character) in hand_chars.iter().enumerate() {
-        hand_values_part_one[i] = *CARD_CODES_PART_ONE.get(character).unwrap();
-        hand_values_part_two[i] = *CARD_CODES_PART_TWO.get(character).unwrap();
+    if hand.chars().count() != 5 {
+        return Err(line.error(hand, "expected a hand of 5 cards"));
     }
-    (hand_values_part_one, hand_values_part_two)
-}
\ No newline at end of file
+
+    for (i, (column, character)) in hand.char_indices().enumerate() {
+        match (CARD_CODES_PART_ONE.get(&character), CARD_CODES_PART_TWO.get(&character)) {
+            (Some(&value_part_one), Some(&value_part_two)) => {
+                hand_values_part_one[i] = value_part_one;
+                hand_values_part_two[i] = value_part_two;
+            },
+            _ => return Err(line.unknown_symbol(&hand[column..]))
+        }
+    }
+    Ok((hand_values_part_one, hand_values_part_two))
+}
+
+
+/// Generate distinct hands with bids of up to a thousand.
+/// The size is the number of hands, at most the number of distinct hands.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut hands: HashSet<String> = HashSet::new();
+    let mut data = String::new();
+
+    while hands.len() < size.min(13_usize.pow(5)) {
+        // Drawing from a few different labels gives a mix of all the hand types.
+        let mut labels: Vec<char> = "23456789TJQKA".chars().collect();
+        rng.shuffle(&mut labels);
+        let different = rng.range(1..6);
+        let hand: String = (0..5).map(| _ | *rng.pick(&labels[..different])).collect();
+
+        if hands.insert(hand.clone()) {
+            data += &format!("{hand} {}\n", rng.range(1..1001));
+        }
+    }
+    data
+}
@@ -1,65 +1,258 @@
 use std::collections::HashMap;
+#[cfg(feature = "fs")]
 use std::fs;
 
-use crate::util;
+use crate::error::{Error, Result};
+use crate::observe;
+use crate::solver::{self, Solution, Solver};
+use crate::util::{self, number_theory};
+use crate::util::number_theory::Congruence;
+use crate::util::random::Rng;
 
-pub fn determine_steps_through_wasteland(file_path: &str) -> (usize, usize) {
-    let data: String = fs::read_to_string(file_path).unwrap();
-    let mut lines = data.lines();
+pub struct HauntedWasteland;
+
+impl Solver for HauntedWasteland {
+    fn day(&self) -> u8 { 8 }
+
+    fn title(&self) -> &'static str { "Haunted Wasteland" }
+
+    fn labels(&self) -> [&'static str; 2] { ["Number of Steps 1", "Number of Steps 2"] }
+
+    fn input_file(&self) -> &'static str { "day_8_haunted_wasteland.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
+    }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_steps_through_wasteland(file_path: &str) -> Result<(usize, u64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(usize, u64)> {
+    let network = parse(data)?;
+    Ok((part_one(&network)?, part_two(&network)?))
+}
+
+/// The loop of left/right steps and the map from each node to its left and right node.
+pub struct Network<'a> {
+    step_indices: Vec<usize>,
+    map: HashMap<&'a str, [&'a str; 2]>
+}
+
+pub fn parse(data: &str) -> Result<Network<'_>> {
+    let mut lines = util::lines(data);
 
     // Get the loop of steps and convert to indices
-    let step_indices: Vec<usize> = lines
-        .next()
-        .unwrap()
-        .replace('L', "0")
-        .replace('R', "1")
-        .chars()
-        .map(| c: char | c.to_digit(10).unwrap() as usize)  // indices must be usize
-        .collect();
+    let steps = lines.next().ok_or_else(|| Error::parse(0, 0, "expected a line of steps"))?;
+    let step_indices: Vec<usize> = steps.text
+        .char_indices()
+        .map(| (column, c) | match c {  // indices must be usize
+            'L' => Ok(0),
+            'R' => Ok(1),
+            _ => Err(steps.unknown_symbol(&steps.text[column..]))
+        })
+        .collect::<Result<_>>()?;
+
+    if step_indices.is_empty() {
+        return Err(steps.error(steps.text, "expected at least one step"));
+    }
 
     // Construct the map
     let mut map: HashMap<&str, [&str; 2]> = HashMap::new();
     for line in lines.skip(1) {
-        let (key, val) = line.split_once('=').unwrap();
-        let (val_1, val_2) = val.split_once(',').unwrap();
+        let (key, val) = line.split_once(line.text, "=")?;
+        let (val_1, val_2) = line.split_once(val, ",")?;
 
         map.insert(
             key.trim(),
             [
-                val_1.strip_prefix(" (").unwrap(),
-                val_2.trim().strip_suffix(')').unwrap()
+                val_1.strip_prefix(" (").ok_or_else(|| line.error(val_1, "expected \" (\""))?,
+                val_2.trim().strip_suffix(')').ok_or_else(|| line.error(val_2, "expected \")\""))?
             ]
         );
     }
 
-    // Traverse through the map part one
-    let mut current_position = "AAA";
-    let mut n_steps_part_one: usize = 0;
-    let mut indices = step_indices.iter().cycle();
-    while current_position != "ZZZ" {
-        current_position = map.get(current_position).unwrap()[*indices.next().unwrap()];
-        n_steps_part_one += 1;
-    }
+    Ok(Network { step_indices, map })
+}
+
+/// Traverse through the map part one
+pub fn part_one(network: &Network) -> Result<usize> {
+    let (n_steps, _) = count_steps(&network.map, &network.step_indices, "AAA", 0, | pos | pos == "ZZZ")?;
+    Ok(n_steps)
+}
 
-    // Traverse through the map part two
-    // The trick is to do each route individually and then compute the LCM for the solution.
-    let mut current_positions: Vec<&str> = map
-        .clone()
-        .into_keys()
+/// Traverse through the map part two
+/// The trick is to do each route individually: every route reaches its end for the first time
+/// and then loops back to that end with a fixed period. The routes end at the same time at the
+/// first step that is in every loop, which the Chinese Remainder Theorem finds.
+pub fn part_two(network: &Network) -> Result<u64> {
+    let Network { step_indices, map } = network;
+    let start_positions: Vec<&str> = map
+        .keys()
+        .copied()
         .filter(| key | key.ends_with('A'))
         .collect();
-    let mut n_steps_part_two: Vec<usize> = Vec::new();
 
-    for pos in current_positions.iter_mut() {
-        let mut indices = step_indices.iter().cycle();
-        let mut n_steps: usize = 0;
+    if start_positions.is_empty() {
+        return Err(Error::unsolvable("there are no nodes ending with 'A'"));
+    }
+
+    let mut loops: Vec<Congruence> = Vec::new();
+    let mut min_steps: usize = 0;
+    for start in start_positions {
+        let (first, period) = find_loop(map, step_indices, start, | pos | pos.ends_with('Z'))?;
+        observe::value(start, format!("first end after {first} steps, then every {period} steps"));
+
+        // The period is at least one step, so the congruence always exists.
+        loops.extend(Congruence::new(first as u128, period as u128));
+        min_steps = min_steps.max(first);
+    }
+
+    number_theory::crt(loops)
+        .and_then(| steps | steps.smallest_at_least(min_steps as u128))
+        .and_then(| steps | u64::try_from(steps).ok())
+        .ok_or_else(|| Error::unsolvable("the routes never end at the same time"))
+}
+
+
+fn find_loop(
+    map: &HashMap<&str, [&str; 2]>,
+    step_indices: &[usize],
+    start: &str,
+    is_end: impl Fn(&str) -> bool + Copy
+) -> Result<(usize, usize)> {
+    // Find the first step at which the route is at an end, and the period with which it keeps reaching an end.
+    // The route repeats once it is back at the first end at the same step of the loop of steps.
+    // Until then every end must be reached at the same interval, otherwise there is no single period.
+    let max_steps: usize = map.len() * step_indices.len();
+    let (first, end) = count_steps(map, step_indices, start, 0, is_end)?;
+
+    let mut position = end;
+    let mut n_steps = first;
+    let mut period: Option<usize> = None;
+    loop {
+        let (interval, next) = count_steps(map, step_indices, position, n_steps, is_end)?;
+        if period.is_some_and(| period | period != interval) {
+            return Err(Error::unsolvable(format!("the route from {start} reaches its ends at irregular intervals")));
+        }
+        period = Some(interval);
+        position = next;
+        n_steps += interval;
+
+        if position == end && (n_steps - first) % step_indices.len() == 0 {
+            return Ok((first, interval));
+        }
+        if n_steps - first > max_steps {
+            return Err(Error::unsolvable(format!("the route from {start} never returns to {end}")));
+        }
+    }
+}
+
+
+fn count_steps<'a>(
+    map: &HashMap<&'a str, [&'a str; 2]>,
+    step_indices: &[usize],
+    start: &'a str,
+    first_step: usize,
+    is_end: impl Fn(&str) -> bool
+) -> Result<(usize, &'a str)> {
+    // Follow the steps from start, beginning at first_step of the loop, until is_end is reached.
+    // At least one step is taken, so starting at an end finds the next end.
+    // After visiting every (position, step index) combination the route only repeats itself.
+    // Returns the number of steps and the end that was reached.
+    let max_steps: usize = map.len() * step_indices.len();
+
+    let mut position = start;
+    let mut n_steps: usize = 0;
+    let mut indices = step_indices.iter().cycle().skip(first_step % step_indices.len());
+
+    loop {
+        if n_steps > max_steps {
+            return Err(Error::unsolvable(format!("the route from {start} never ends")));
+        }
+
+        position = match (map.get(position), indices.next()) {
+            (Some(next), Some(&index)) => next[index],
+            _ => return Err(Error::unsolvable(format!("node {position} is not in the map")))
+        };
+        n_steps += 1;
+
+        if is_end(position) {
+            return Ok((n_steps, position));
+        }
+    }
+}
+
+
+/// Generate a loop of steps and a network with a route from each of six starts.
+/// The size is the number of steps in the loop, at most 600 so that every node has a name of three characters.
+/// Each route ends after the same number of steps modulo the loop, and then loops through its end
+/// every prime number of loops, so that the routes always end at the same time.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let steps = size.clamp(1, 600);
+    let step_indices: Vec<usize> = (0..steps).map(| _ | rng.range(0..2)).collect();
+
+    // The nodes between the start and the end of a route never end with 'A' or 'Z'.
+    let mut names: Vec<String> = SYMBOLS
+        .chars()
+        .flat_map(| first | SYMBOLS.chars().map(move | second | format!("{first}{second}")))
+        .flat_map(| prefix | SYMBOLS[1..25].chars().chain(SYMBOLS[26..].chars()).map(move | last | format!("{prefix}{last}")))
+        .collect();
+    rng.shuffle(&mut names);
+
+    let mut prefixes: Vec<String> = LETTERS
+        .chars()
+        .flat_map(| first | LETTERS.chars().map(move | second | format!("{first}{second}")))
+        .filter(| prefix | prefix != "AA" && prefix != "ZZ")
+        .collect();
+    rng.shuffle(&mut prefixes);
 
-        while !pos.ends_with('Z') {
-            *pos = map.get(pos).unwrap()[*indices.next().unwrap()];
-            n_steps += 1
+    let mut primes = [3, 5, 7, 11, 13, 17];
+    rng.shuffle(&mut primes);
+    let first_step = rng.range(0..steps);
+
+    // Every route is a tail from its start into a cycle, each node follows the route at the step it is visited.
+    let mut nodes: Vec<(String, [usize; 2])> = Vec::new();
+    for (route, prime) in primes.into_iter().enumerate() {
+        let (start, end) = match route {
+            0 => ("AAA".to_string(), "ZZZ".to_string()),
+            _ => (format!("{}A", prefixes[2 * route]), format!("{}Z", prefixes[2 * route + 1]))
+        };
+
+        let tail = rng.range(1..steps + 1);
+        let cycle = prime * steps;
+        let end_index = tail + (first_step + steps * rng.range(0..prime) + cycle - tail) % cycle;
+
+        let offset = nodes.len();
+        for index in 0..tail + cycle {
+            let name = match index {
+                0 => start.clone(),
+                index if index == end_index => end.clone(),
+                _ => names.pop().unwrap()
+            };
+            let next = if index + 1 < tail + cycle { index + 1 } else { tail };
+
+            let mut children = [offset + rng.range(0..tail + cycle); 2];
+            children[step_indices[index % steps]] = offset + next;
+            nodes.push((name, children));
         }
-        n_steps_part_two.push(n_steps);
     }
 
-    (n_steps_part_one, util::lcm(&n_steps_part_two))
-}
\ No newline at end of file
+    let mut lines: Vec<String> = nodes
+        .iter()
+        .map(| (name, [left, right]) | format!("{name} = ({}, {})\n", nodes[*left].0, nodes[*right].0))
+        .collect();
+    rng.shuffle(&mut lines);
+
+    let steps: String = step_indices.iter().map(| &index | if index == 0 { 'L' } else { 'R' }).collect();
+    format!("{steps}\n\n{}", lines.concat())
+}
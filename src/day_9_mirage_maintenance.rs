@@ -1,45 +1,97 @@
+#[cfg(feature = "fs")]
 use std::fs;
 
-use itertools::Itertools;
+use crate::error::{Error, Result};
+use crate::solver::{self, Solution, Solver};
+use crate::util;
+use crate::util::polynomial::Polynomial;
+use crate::util::random::Rng;
 
-pub fn determine_sum_of_values(file_path: &str) -> (isize, isize) {
-    let data = fs::read_to_string(file_path).unwrap();
+pub struct MirageMaintenance;
 
-    let mut prediction_sum_part_one: isize = 0;
-    let mut prediction_sum_part_two: isize = 0;
+impl Solver for MirageMaintenance {
+    fn day(&self) -> u8 { 9 }
 
-    for line in data.lines() {
-        let mut values: Vec<isize> = line
-            .split_whitespace()
-            .map(| num | num.parse::<isize>().unwrap())
-            .collect();
+    fn title(&self) -> &'static str { "Mirage Maintenance" }
 
-        prediction_sum_part_one += predict_number(values.clone());
+    fn labels(&self) -> [&'static str; 2] { ["Sum of Values 1", "Sum of Values 2"] }
 
-        values.reverse();
-        prediction_sum_part_two += predict_number(values);
+    fn input_file(&self) -> &'static str { "day_9_mirage_maintenance.txt" }
+
+    fn solve(&self, data: &str) -> Result<Solution> {
+        solver::solve_timed(data, parse, part_one, part_two)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> String {
+        generate(rng, size)
     }
+}
+
+/// Read the puzzle input from a file and solve it.
+#[cfg(feature = "fs")]
+pub fn determine_sum_of_values(file_path: &str) -> Result<(i64, i64)> {
+    solve(&fs::read_to_string(file_path)?)
+}
+
+pub fn solve(data: &str) -> Result<(i64, i64)> {
+    let histories = parse(data)?;
+    Ok((part_one(&histories)?, part_two(&histories)?))
+}
+
+pub fn parse(data: &str) -> Result<Vec<Vec<i64>>> {
+    util::lines(data)
+        .map(| line | {
+            let values: Vec<i64> = line.text
+                .split_whitespace()
+                .map(| num | line.parse::<i64>(num))
+                .collect::<Result<_>>()?;
 
-    (prediction_sum_part_one, prediction_sum_part_two)
+            if values.is_empty() {
+                return Err(line.error(line.text, "expected a history of values"));
+            }
+            Ok(values)
+        })
+        .collect()
+}
+
+pub fn part_one(histories: &[Vec<i64>]) -> Result<i64> {
+    histories.iter().map(| values | predict_number(values, values.len() as i128)).sum()
+}
+
+/// Predicting the previous value is evaluating the fitted polynomial before the first value.
+pub fn part_two(histories: &[Vec<i64>]) -> Result<i64> {
+    histories.iter().map(| values | predict_number(values, -1)).sum()
 }
 
 
 #[inline]
-fn predict_number(mut values: Vec<isize>) -> isize {
-    // Predict the next value.
-    //
-    // Perform subtraction on windows
-    // Add last value
-    // Continue until all values are equal (because for next step they would all be zero)
-    let mut prediction: isize = *values.last().unwrap();
-
-    while !values.iter().all_equal() {
-        values = values
-            .windows(2)
-            .map(| num | num[1] - num[0])
-            .collect();
-
-        prediction += values.last().unwrap();
+fn predict_number(values: &[i64], x: i128) -> Result<i64> {
+    // The repeated differences of the history are the forward differences of the polynomial through it.
+    // Predict the value at position x of the history by evaluating that polynomial.
+    let samples: Vec<i128> = values.iter().map(| &value | value as i128).collect();
+    Polynomial::fit(&samples)
+        .and_then(| polynomial | polynomial.evaluate(x))
+        .and_then(| prediction | i64::try_from(prediction).ok())
+        .ok_or_else(|| Error::unsolvable("the prediction overflows"))
+}
+
+
+/// Generate histories of twenty-one values of polynomials up to degree seven.
+/// The size is the number of histories.
+pub fn generate(rng: &mut Rng, size: usize) -> String {
+    let mut data = String::new();
+
+    for _ in 0..size {
+        // Summing the differences level by level gives the values of the polynomial.
+        let mut differences: Vec<i64> = (0..rng.range(1..9)).map(| _ | rng.range(0..19) as i64 - 9).collect();
+        let mut values: Vec<String> = Vec::new();
+        for _ in 0..21 {
+            values.push(differences[0].to_string());
+            for level in 0..differences.len() - 1 {
+                differences[level] += differences[level + 1];
+            }
+        }
+        data += &(values.join(" ") + "\n");
     }
-    prediction
-}
\ No newline at end of file
+    data
+}
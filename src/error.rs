@@ -0,0 +1,73 @@
+use std::{fmt, io};
+
+/// Result type of the puzzle solvers.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Errors that can occur while reading, parsing or solving a puzzle input.
+/// Lines and columns are stored zero-based and displayed one-based.
+#[derive(Debug)]
+pub enum Error {
+    /// The puzzle input could not be read.
+    Io(io::Error),
+
+    /// A line of the puzzle input is malformed.
+    Parse { line: usize, column: usize, message: String },
+
+    /// The puzzle input contains a symbol that has no meaning in the puzzle.
+    UnknownSymbol { line: usize, column: usize, symbol: char },
+
+    /// The puzzle input is well-formed, but the puzzle has no solution.
+    Unsolvable(String),
+
+    /// The solver panicked, this is a bug in the solver.
+    Panic,
+
+    /// The puzzle input could not be downloaded from the website.
+    Download(String)
+}
+
+impl Error {
+    pub fn parse(line: usize, column: usize, message: impl Into<String>) -> Self {
+        Self::Parse { line, column, message: message.into() }
+    }
+
+    pub fn unknown_symbol(line: usize, column: usize, symbol: char) -> Self {
+        Self::UnknownSymbol { line, column, symbol }
+    }
+
+    pub fn unsolvable(message: impl Into<String>) -> Self {
+        Self::Unsolvable(message.into())
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "failed to read the input: {error}"),
+            Error::Parse { line, column, message } => {
+                write!(f, "line {}, column {}: {message}", line + 1, column + 1)
+            },
+            Error::UnknownSymbol { line, column, symbol } => {
+                write!(f, "line {}, column {}: unknown symbol {symbol:?}", line + 1, column + 1)
+            },
+            Error::Unsolvable(message) => write!(f, "no solution: {message}"),
+            Error::Panic => write!(f, "the solver panicked"),
+            Error::Download(message) => write!(f, "failed to download the input: {message}")
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
@@ -0,0 +1,240 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use std::{env, fs, io, thread};
+
+use crate::error::{Error, Result};
+use crate::inputs::Profile;
+use crate::solver::Solver;
+
+/// The website of Advent of Code.
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable with the session token, which takes precedence over [`SESSION_FILE`].
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// File in the working directory with the session token, it is in `.gitignore`.
+pub const SESSION_FILE: &str = ".aoc_session";
+
+/// Default time between two downloads.
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Identifies the downloads to the website, as its operator asks of automated tools.
+const USER_AGENT: &str = "advent_of_code_2023 input fetcher (https://github.com/MaxHoek91/advent_of_code_2023)";
+
+
+/// The status code and body of the response to an HTTP request.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String
+}
+
+/// The HTTP client that downloads the puzzle inputs, a trait so that tests can use a stub.
+pub trait Http {
+    /// Send a GET request with the given headers.
+    /// A response with an error status is a response, only a failure to get any response is an error.
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Response>;
+}
+
+/// The HTTP client of `ureq`, for http and https URLs.
+pub struct UreqHttp {
+    agent: ureq::Agent
+}
+
+impl Default for UreqHttp {
+    fn default() -> Self {
+        Self { agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build() }
+    }
+}
+
+impl Http for UreqHttp {
+    fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Response> {
+        let request = headers
+            .iter()
+            .fold(self.agent.get(url), | request, &(name, value) | request.set(name, value));
+
+        let response = match request.call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(_, response)) => response,
+            Err(error) => return Err(io::Error::other(error))
+        };
+        Ok(Response { status: response.status(), body: response.into_string()? })
+    }
+}
+
+
+/// Keeps requests at least an interval apart by waiting before each one.
+#[derive(Debug)]
+pub struct RateLimit {
+    interval: Duration,
+    last_request: Option<Instant>
+}
+
+impl RateLimit {
+    pub fn new(interval: Duration) -> Self {
+        Self { interval, last_request: None }
+    }
+
+    /// Wait until the interval since the previous request has passed.
+    pub fn wait(&mut self) {
+        if let Some(last_request) = self.last_request {
+            thread::sleep(self.interval.saturating_sub(last_request.elapsed()));
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+
+/// The session token from [`SESSION_VAR`] or [`SESSION_FILE`].
+pub fn session_token() -> Result<String> {
+    let token = match env::var(SESSION_VAR).ok().filter(| token | !token.trim().is_empty()) {
+        Some(token) => token,
+        None => fs::read_to_string(SESSION_FILE).map_err(| error | {
+            Error::Download(format!("no session token in ${SESSION_VAR} or in {SESSION_FILE}: {error}"))
+        })?
+    };
+
+    let token = token.trim();
+    match token.is_empty() || token.contains(char::is_whitespace) {
+        true => Err(Error::Download("the session token must be a single word".to_string())),
+        false => Ok(token.to_string())
+    }
+}
+
+
+/// Where a fetched input came from.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Fetched {
+    /// The input was already in the profile.
+    Cached(PathBuf),
+    /// The input was downloaded into the profile.
+    Downloaded(PathBuf)
+}
+
+/// Downloads the puzzle inputs into a profile, under the file names the solvers read.
+/// The profile is the cache: an input that is already in it is never downloaded again.
+pub struct Fetcher<H> {
+    http: H,
+    base_url: String,
+    session: String,
+    rate_limit: RateLimit
+}
+
+impl<H: Http> Fetcher<H> {
+    /// A fetcher from the website at `base_url`, e.g. [`BASE_URL`], with at least `interval` between downloads.
+    pub fn new(http: H, base_url: &str, session: &str, interval: Duration) -> Self {
+        Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            rate_limit: RateLimit::new(interval)
+        }
+    }
+
+    /// Make sure the input of a solver is in the profile, download it if it is not.
+    pub fn fetch(&mut self, solver: &dyn Solver, profile: &Profile) -> Result<Fetched> {
+        let file_path = profile.input_path(solver);
+        if file_path.is_file() {
+            return Ok(Fetched::Cached(file_path));
+        }
+
+        self.rate_limit.wait();
+        let url = format!("{}/2023/day/{}/input", self.base_url, solver.day());
+        let cookie = format!("session={}", self.session);
+        let response = self.http.get(&url, &[("Cookie", &cookie), ("User-Agent", USER_AGENT)])?;
+
+        match response.status {
+            200 if !response.body.is_empty() => {},
+            200 => return Err(Error::Download(format!("the input of day {} is empty", solver.day()))),
+            400 | 401 | 403 => return Err(Error::Download("the session token was rejected, log in again".to_string())),
+            404 => return Err(Error::Download(format!("day {} is not available yet", solver.day()))),
+            status => return Err(Error::Download(format!("{url} returned status {status}")))
+        }
+
+        // Write to a temporary file first, so that an interrupted download is never taken for a cached input.
+        fs::create_dir_all(profile.dir())?;
+        let partial_path = file_path.with_extension("part");
+        fs::write(&partial_path, &response.body)?;
+        fs::rename(&partial_path, &file_path)?;
+
+        Ok(Fetched::Downloaded(file_path))
+    }
+}
+
+
+// The tests fetch the input of day one.
+#[cfg(all(test, feature = "day_1"))]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Answers every request with the same response, and records the requests.
+    struct StubHttp {
+        response: Response,
+        requests: RefCell<Vec<(String, Vec<String>)>>
+    }
+
+    impl StubHttp {
+        fn new(status: u16, body: &str) -> Self {
+            Self { response: Response { status, body: body.to_string() }, requests: RefCell::default() }
+        }
+    }
+
+    impl Http for &StubHttp {
+        fn get(&self, url: &str, headers: &[(&str, &str)]) -> io::Result<Response> {
+            let headers = headers.iter().map(| (name, value) | format!("{name}: {value}")).collect();
+            self.requests.borrow_mut().push((url.to_string(), headers));
+            Ok(self.response.clone())
+        }
+    }
+
+    fn profile(name: &str) -> Profile {
+        let data_dir = env::temp_dir().join(format!("aoc_2023_fetch_{}", std::process::id()));
+        Profile::in_dir(&data_dir, name)
+    }
+
+    #[test]
+    fn test_download_and_cache() {
+        let http = StubHttp::new(200, "1abc2\n");
+        let profile = profile("cache");
+        let solver = crate::solver(1).unwrap();
+        let mut fetcher = Fetcher::new(&http, "http://localhost/", "secret", Duration::ZERO);
+
+        let file_path = profile.input_path(solver);
+        assert_eq!(fetcher.fetch(solver, &profile).unwrap(), Fetched::Downloaded(file_path.clone()));
+        assert_eq!(fetcher.fetch(solver, &profile).unwrap(), Fetched::Cached(file_path.clone()));
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "1abc2\n");
+
+        let requests = http.requests.borrow();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].0, "http://localhost/2023/day/1/input");
+        assert!(requests[0].1.contains(&"Cookie: session=secret".to_string()));
+
+        fs::remove_dir_all(profile.dir()).unwrap();
+    }
+
+    #[test]
+    fn test_failed_download_is_not_cached() {
+        let profile = profile("failed");
+        let solver = crate::solver(1).unwrap();
+
+        for (status, body) in [(400, "Puzzle inputs differ by user."), (404, "Not found"), (200, "")] {
+            let http = StubHttp::new(status, body);
+            let mut fetcher = Fetcher::new(&http, BASE_URL, "secret", Duration::ZERO);
+            assert!(matches!(fetcher.fetch(solver, &profile), Err(Error::Download(_))));
+            assert!(!profile.input_path(solver).exists());
+        }
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut rate_limit = RateLimit::new(Duration::from_millis(50));
+        let timer = Instant::now();
+        rate_limit.wait();
+        assert!(timer.elapsed() < Duration::from_millis(50));
+        rate_limit.wait();
+        rate_limit.wait();
+        assert!(timer.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::solver::Solver;
+
+/// Directory that holds the input profiles.
+pub const DATA_DIR: &str = "./data";
+
+/// Name of the profile whose inputs are directly in the data directory.
+pub const PERSONAL: &str = "personal";
+
+/// Name of the answers manifest in a profile directory.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// A named set of puzzle inputs, e.g. the examples of the puzzle texts or the inputs of a team member.
+/// The personal profile is the top level of the data directory,
+/// every other profile is a subdirectory named after the profile.
+/// Each day reads the file named by [`Solver::input_file`] from the profile directory.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Profile {
+    name: String,
+    dir: PathBuf
+}
+
+impl Profile {
+    /// The profile with this name in [`DATA_DIR`].
+    pub fn new(name: &str) -> Self {
+        Self::in_dir(Path::new(DATA_DIR), name)
+    }
+
+    /// The profile with this name in another data directory.
+    pub fn in_dir(data_dir: &Path, name: &str) -> Self {
+        let dir = match name {
+            PERSONAL => data_dir.to_path_buf(),
+            _ => data_dir.join(name)
+        };
+        Self { name: name.to_string(), dir }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the puzzle input of a day.
+    pub fn input_path(&self, solver: &dyn Solver) -> PathBuf {
+        self.dir.join(solver.input_file())
+    }
+
+    /// Path of the answers manifest, if the profile has one.
+    pub fn answers_path(&self) -> Option<PathBuf> {
+        Some(self.dir.join(ANSWERS_FILE)).filter(| path | path.is_file())
+    }
+}
+
+
+/// All profiles in [`DATA_DIR`], see [`profiles_in`].
+pub fn profiles() -> Result<Vec<Profile>> {
+    profiles_in(Path::new(DATA_DIR))
+}
+
+/// All profiles in a data directory: the personal profile, then one per subdirectory sorted by name.
+pub fn profiles_in(data_dir: &Path) -> Result<Vec<Profile>> {
+    let mut names: Vec<String> = Vec::new();
+    for entry in fs::read_dir(data_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            names.extend(entry.file_name().to_str().filter(| &name | name != PERSONAL).map(String::from));
+        }
+    }
+    names.sort();
+
+    Ok([PERSONAL.to_string()]
+        .into_iter()
+        .chain(names)
+        .map(| name | Profile::in_dir(data_dir, &name))
+        .collect())
+}
+
+/// Check that a profile name can be used as a directory name in the data directory.
+pub fn valid_profile_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name.chars().all(| c | c.is_alphanumeric() || "-_.".contains(c))
+}
+
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "day_1")]
+    use crate::day_1_trebuchet::Trebuchet;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "day_1")]
+    fn test_profiles() {
+        let data_dir = std::env::temp_dir().join(format!("aoc_2023_profiles_{}", std::process::id()));
+        for dir in ["example", "alice", "personal"] {
+            fs::create_dir_all(data_dir.join(dir)).unwrap();
+        }
+        fs::write(data_dir.join("day_1_trebuchet.txt"), "").unwrap();
+        fs::write(data_dir.join("alice").join(ANSWERS_FILE), "").unwrap();
+
+        let profiles = profiles_in(&data_dir).unwrap();
+        let names: Vec<&str> = profiles.iter().map(| profile | profile.name()).collect();
+        assert_eq!(names, ["personal", "alice", "example"]);
+
+        assert_eq!(profiles[0].dir(), data_dir);
+        assert_eq!(profiles[0].input_path(&Trebuchet), data_dir.join("day_1_trebuchet.txt"));
+        assert_eq!(profiles[2].input_path(&Trebuchet), data_dir.join("example").join("day_1_trebuchet.txt"));
+
+        assert_eq!(profiles[0].answers_path(), None);
+        assert_eq!(profiles[1].answers_path(), Some(data_dir.join("alice").join(ANSWERS_FILE)));
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[test]
+    fn test_valid_profile_name() {
+        assert!(valid_profile_name("example"));
+        assert!(valid_profile_name("team-alice_2"));
+        assert!(!valid_profile_name(""));
+        assert!(!valid_profile_name(".."));
+        assert!(!valid_profile_name("../secrets"));
+    }
+}
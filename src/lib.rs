@@ -1,364 +1,358 @@
-use std::path::Path;
+#[cfg(feature = "fs")]
+use std::{fs, io};
+use std::ops::RangeInclusive;
+#[cfg(feature = "fs")]
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+#[cfg(feature = "fs")]
 use std::time::Instant;
 
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::answers::Verdict;
+#[cfg(feature = "fs")]
+use crate::error::Error;
+use crate::error::Result;
+#[cfg(feature = "fs")]
+use crate::inputs::Profile;
+#[cfg(feature = "fs")]
+use crate::output::Format;
+use crate::solver::{Answers, Solver, Timings};
+use crate::util::random::Rng;
+
+#[cfg(feature = "day_1")]
 pub mod day_1_trebuchet;
+#[cfg(feature = "day_2")]
 pub mod day_2_cube_conundrum;
+#[cfg(feature = "day_3")]
 pub mod day_3_gear_ratios;
+#[cfg(feature = "day_4")]
 pub mod day_4_scratchcards;
+#[cfg(feature = "day_5")]
 pub mod day_5_seed_soil;
+#[cfg(feature = "day_6")]
 pub mod day_6_wait_for_it;
+#[cfg(feature = "day_7")]
 pub mod day_7_camel_cards;
+#[cfg(feature = "day_8")]
 pub mod day_8_haunted_wasteland;
+#[cfg(feature = "day_9")]
 pub mod day_9_mirage_maintenance;
+#[cfg(feature = "day_10")]
 pub mod day_10_pipe_maze;
+#[cfg(feature = "day_11")]
 pub mod day_11_cosmic_expansion;
+#[cfg(feature = "day_12")]
 pub mod day_12_hot_springs;
+#[cfg(feature = "day_13")]
 pub mod day_13_point_of_incidence;
+#[cfg(feature = "day_14")]
 pub mod day_14_parabolic_reflector_dish;
+#[cfg(feature = "day_15")]
 pub mod day_15_lens_library;
+#[cfg(feature = "day_16")]
 pub mod day_16_the_floor_will_be_lava;
+#[cfg(feature = "day_17")]
 pub mod day_17_clumsy_crucible;
+#[cfg(feature = "day_18")]
 pub mod day_18_lavaduct_lagoon;
+#[cfg(feature = "day_19")]
 pub mod day_19_aplenty;
+#[cfg(feature = "day_20")]
 pub mod day_20_pulse_propagation;
+#[cfg(feature = "day_21")]
 pub mod day_21_step_counter;
+#[cfg(feature = "day_22")]
 pub mod day_22_sand_slabs;
 
-mod util;
-
-
-pub fn solve_all() {
-    println!("Advent of Code 2023\n");
-
-    day_1();
-    day_2();
-    day_3();
-    day_4();
-    day_5();
-    day_6();
-    day_7();
-    day_8();
-    day_9();
-    day_10();
-    day_11();
-    day_12();
-    day_13();
-    day_14();
-    day_15();
-    day_16();
-    day_17();
-    day_18();
-    day_19();
-    day_20();
-    day_21();
-    day_22();
+pub mod answers;
+pub mod bench;
+pub mod error;
+#[cfg(feature = "fetch")]
+pub mod fetch;
+#[cfg(feature = "fs")]
+pub mod inputs;
+pub mod observe;
+pub mod output;
+#[cfg(feature = "render")]
+pub mod render;
+pub mod solver;
+pub mod util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+
+/// The days that have been solved.
+pub const DAYS: RangeInclusive<u8> = 1..=22;
+
+/// Registry of the daily puzzles, ordered by day.
+/// Only the days enabled by the cargo features are in it, e.g. `day_6`, or `all_days` which is a default feature.
+pub static SOLVERS: &[&dyn Solver] = &[
+    #[cfg(feature = "day_1")]
+    &day_1_trebuchet::Trebuchet,
+    #[cfg(feature = "day_2")]
+    &day_2_cube_conundrum::CubeConundrum,
+    #[cfg(feature = "day_3")]
+    &day_3_gear_ratios::GearRatios,
+    #[cfg(feature = "day_4")]
+    &day_4_scratchcards::Scratchcards,
+    #[cfg(feature = "day_5")]
+    &day_5_seed_soil::SeedToLocation,
+    #[cfg(feature = "day_6")]
+    &day_6_wait_for_it::WaitForIt,
+    #[cfg(feature = "day_7")]
+    &day_7_camel_cards::CamelCards,
+    #[cfg(feature = "day_8")]
+    &day_8_haunted_wasteland::HauntedWasteland,
+    #[cfg(feature = "day_9")]
+    &day_9_mirage_maintenance::MirageMaintenance,
+    #[cfg(feature = "day_10")]
+    &day_10_pipe_maze::PipeMaze,
+    #[cfg(feature = "day_11")]
+    &day_11_cosmic_expansion::CosmicExpansion,
+    #[cfg(feature = "day_12")]
+    &day_12_hot_springs::HotSprings,
+    #[cfg(feature = "day_13")]
+    &day_13_point_of_incidence::PointOfIncidence,
+    #[cfg(feature = "day_14")]
+    &day_14_parabolic_reflector_dish::ParabolicReflectorDish,
+    #[cfg(feature = "day_15")]
+    &day_15_lens_library::LensLibrary,
+    #[cfg(feature = "day_16")]
+    &day_16_the_floor_will_be_lava::TheFloorWillBeLava,
+    #[cfg(feature = "day_17")]
+    &day_17_clumsy_crucible::ClumsyCrucible,
+    #[cfg(feature = "day_18")]
+    &day_18_lavaduct_lagoon::LavaductLagoon,
+    #[cfg(feature = "day_19")]
+    &day_19_aplenty::Aplenty,
+    #[cfg(feature = "day_20")]
+    &day_20_pulse_propagation::PulsePropagation,
+    #[cfg(feature = "day_21")]
+    &day_21_step_counter::StepCounter,
+    #[cfg(feature = "day_22")]
+    &day_22_sand_slabs::SandSlabs,
+];
+
+/// Which part(s) of a puzzle to report.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+    Both
 }
 
-fn day_1() {
-    let file = Path::new("./data/day_1_trebuchet.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let cal_value = day_1_trebuchet::determine_calibration_values(file);
-
-    println!(
-        "Day 1: Trebuchet\n\
-        Run Time: {:?}\n\
-        Sum of Calibration Values 1: {}\n\
-        Sum of Calibration Values 2: {}\n",
-        timer.elapsed(), cal_value.0, cal_value.1
-    );
-}
 
-fn day_2() {
-    let file = Path::new("./data/day_2_cube_conundrum.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (possible_games_sum, cube_power) = day_2_cube_conundrum::determine_games_and_power(file);
-
-    println!(
-        "Day 2: Cube Conundrum\n\
-        Run Time: {:?}\n\
-        Possible Games: {}\n\
-        Cube Power: {}\n",
-        timer.elapsed(), possible_games_sum, cube_power
-    );
+/// Find the solver of a day in the registry.
+pub fn solver(day: u8) -> Option<&'static dyn Solver> {
+    SOLVERS.iter().find(| solver | solver.day() == day).copied()
 }
 
-fn day_3() {
-    let file = Path::new("./data/day_3_gear_ratios.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (part_number, gear_ratio) = day_3_gear_ratios::determine_part_number_and_gear_ratio(file);
-
-    println!(
-        "Day 3: Gear Ratios\n\
-        Run Time: {:?}\n\
-        Part Number: {}\n\
-        Gear Ratio: {}\n",
-        timer.elapsed(), part_number, gear_ratio
-    );
-}
 
-fn day_4() {
-    let file = Path::new("./data/day_4_scratchcards.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (points, cards) = day_4_scratchcards::determine_scratchcards_and_points(file);
-
-    println!(
-        "Day 4: Scratchcards\n\
-        Run Time: {:?}\n\
-        Scratchcard Points: {}\n\
-        Scratchcard Total: {}\n",
-        timer.elapsed(), points, cards
-    );
+/// Generate a random puzzle input of a day, see [`Solver::generate`].
+/// The same day, seed and size always generate the same input.
+pub fn generate_input(day: u8, seed: u64, size: usize) -> Option<String> {
+    solver(day).map(| solver | solver.generate(&mut Rng::new(seed), size))
 }
 
-fn day_5() {
-    let file = Path::new("./data/day_5_seed_to_location.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let closest_location = day_5_seed_soil::determine_closest_location(file);
-
-    println!(
-        "Day 5: Seed-to-Location\n\
-        Run Time: {:?}\n\
-        Closest Location 1: {}\n\
-        Closest Location 2: {}\n",
-        timer.elapsed(), closest_location.0, closest_location.1
-    );
-}
 
-fn day_6() {
-    let file = Path::new("./data/day_6_boat_race.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let ways_to_win = day_6_wait_for_it::determine_winning_races(file);
-
-    println!(
-        "Day 6: Wait for it (Boat Race)\n\
-        Run Time: {:?}\n\
-        Ways to Win 1: {}\n\
-        Ways to Win 2: {}\n",
-        timer.elapsed(), ways_to_win.0, ways_to_win.1
-    );
-}
+/// Solve all days and print the answers.
+/// Returns false if any of the days failed to solve.
+#[cfg(feature = "fs")]
+pub fn solve_all() -> bool {
+    println!("Advent of Code 2023\n");
 
-fn day_7() {
-    let file = Path::new("./data/day_7_camel_cards.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let winnings = day_7_camel_cards::determine_total_winnings(file);
-
-    println!(
-        "Day 7: Camel Cards\n\
-        Run Time: {:?}\n\
-        Winnings 1: {}\n\
-        Winnings 2: {}\n",
-        timer.elapsed(), winnings.0, winnings.1
-    );
+    // Count the failures instead of using `all` so that every day is attempted.
+    let failures = DAYS.filter(| &day | !solve_day(day, None, Part::Both)).count();
+    failures == 0
 }
 
-fn day_8() {
-    let file = Path::new("./data/day_8_haunted_wasteland.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let number_of_steps = day_8_haunted_wasteland::determine_steps_through_wasteland(file);
-
-    println!(
-        "Day 8: Haunted Wasteland\n\
-        Run Time: {:?}\n\
-        Number of Steps 1: {}\n\
-        Number of Steps 2: {}\n",
-        timer.elapsed(), number_of_steps.0, number_of_steps.1
-    );
-}
 
-fn day_9() {
-    let file = Path::new("./data/day_9_mirage_maintenance.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let sum_of_values = day_9_mirage_maintenance::determine_sum_of_values(file);
-
-    println!(
-        "Day 9: Mirage Maintenance\n\
-        Run Time: {:?}\n\
-        Sum of Values 1: {}\n\
-        Sum of Values 2: {}\n",
-        timer.elapsed(), sum_of_values.0, sum_of_values.1
-    );
+/// Where to read the puzzle input from.
+#[cfg(feature = "fs")]
+#[derive(Debug, Clone, Copy)]
+pub enum Source<'a> {
+    /// A single file, "-" reads from stdin.
+    File(&'a str),
+    /// The input of the day in a profile.
+    Profile(&'a Profile)
 }
 
-fn day_10() {
-    let file = Path::new("./data/day_10_pipe_maze.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (farthest_distance, enclosed) = day_10_pipe_maze::determine_longest_loop_and_enclosed_tiles(file);
-
-    println!(
-        "Day 10: Pipe Maze\n\
-        Run Time: {:?}\n\
-        Farthest Distance: {}\n\
-        Enclosed Tiles: {}\n",
-        timer.elapsed(), farthest_distance, enclosed
-    );
+#[cfg(feature = "fs")]
+impl<'a> Source<'a> {
+    /// The input file if given, otherwise the input of the day in a profile.
+    pub fn new(file_path: Option<&'a str>, profile: &'a Profile) -> Self {
+        file_path.map_or(Source::Profile(profile), Source::File)
+    }
 }
 
-fn day_11() {
-    let file = Path::new("./data/day_11_cosmic_expansion.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let sum_of_distances = day_11_cosmic_expansion::determine_sum_of_distances(file);
-
-    println!(
-        "Day 11: Cosmic Expansion\n\
-        Run Time: {:?}\n\
-        Sum of Distances 1: {}\n\
-        Sum of Distances 2: {}\n",
-        timer.elapsed(), sum_of_distances.0, sum_of_distances.1
-    );
+/// Read the puzzle input of a solver.
+#[cfg(feature = "fs")]
+pub fn read_input(solver: &dyn Solver, source: Source) -> Result<String> {
+    let data = match source {
+        Source::File("-") => io::read_to_string(io::stdin())?,
+        Source::File(file_path) => fs::read_to_string(file_path)?,
+        Source::Profile(profile) => fs::read_to_string(profile.input_path(solver))?
+    };
+    Ok(data)
 }
 
-fn day_12() {
-    let file = Path::new("./data/day_12_hot_springs.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let total_arrangements = day_12_hot_springs::determine_sum_of_arrangements(file);
-
-    println!(
-        "Day 12: Hot Springs\n\
-        Run Time: {:?}\n\
-        Total Arrangements 1: {}\n\
-        Total Arrangements 2: {}\n",
-        timer.elapsed(), total_arrangements.0, total_arrangements.1
-    );
-}
 
-fn day_13() {
-    let file = Path::new("./data/day_13_point_of_incidence.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let sum_of_notes = day_13_point_of_incidence::determine_sum_of_notes(file);
-
-    println!(
-        "Day 13: Point of Incidence\n\
-        Run Time: {:?}\n\
-        Sum of Notes: {}\n\
-        Sum of Notes with Smudge: {}\n",
-        timer.elapsed(), sum_of_notes.0, sum_of_notes.1
-    );
+/// The outcome of solving a single day.
+#[derive(Debug)]
+pub struct Record {
+    pub day: u8,
+    pub title: &'static str,
+    /// Name of the input profile, None if the input was read from a file.
+    pub profile: Option<String>,
+    pub labels: [&'static str; 2],
+    pub answers: Result<Answers>,
+    /// Time spent parsing and solving each part, zero if solving failed.
+    pub timings: Timings,
+    /// Total time spent solving, including the overhead of the runner.
+    pub run_time: Duration,
+    /// Verdicts of part one and two, if the answers were checked against a manifest.
+    pub verdicts: Option<[Verdict; 2]>,
 }
 
-fn day_14() {
-    let file = Path::new("./data/day_14_parabolic_reflector_dish.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let total_load = day_14_parabolic_reflector_dish::determine_total_load(file);
-
-    println!(
-        "Day 14: Parabolic Reflector Dish\n\
-        Run Time: {:?}\n\
-        Total Load 1: {}\n\
-        Total Load 2: {}\n",
-        timer.elapsed(), total_load.0, total_load.1
-    );
-}
-
-fn day_15() {
-    let file = Path::new("./data/day_15_lens_library.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (hash_sum, focusing_power) = day_15_lens_library::determine_hash(file);
-
-    println!(
-        "Day 15: Lens Library\n\
-        Run Time: {:?}\n\
-        Hash Sum: {}\n\
-        Focusing Power: {}\n",
-        timer.elapsed(), hash_sum, focusing_power
-    );
+impl Record {
+    /// True if the day failed to solve, or if a selected part failed or was verified to be wrong.
+    /// A part that fails as the manifest expects, because it has no answer, does not count.
+    pub fn failed(&self, part: Part) -> bool {
+        let Ok(answers) = &self.answers else {
+            return true;
+        };
+
+        let part_failed = | index: usize | match self.verdicts.as_ref().map(| verdicts | &verdicts[index]) {
+            Some(Verdict::Pass) => false,
+            Some(Verdict::Fail { .. }) => true,
+            Some(Verdict::Unknown) | None => answers[index].is_err()
+        };
+        match part {
+            Part::One => part_failed(0),
+            Part::Two => part_failed(1),
+            Part::Both => part_failed(0) || part_failed(1)
+        }
+    }
 }
 
-fn day_16() {
-    let file = Path::new("./data/day_16_the_floor_will_be_lava.txt").to_str().unwrap();
+/// Read the input of a solver and solve it, without printing anything.
+/// The global observer, if any, receives the events of the solver, see [`observe::set_global_observer`].
+#[cfg(feature = "fs")]
+pub fn run_day(solver: &dyn Solver, source: Source) -> Record {
+    let mut record = Record {
+        day: solver.day(),
+        title: solver.title(),
+        profile: match source {
+            Source::File(_) => None,
+            Source::Profile(profile) => Some(profile.name().to_string())
+        },
+        labels: solver.labels(),
+        answers: Err(Error::Panic),
+        timings: Timings::default(),
+        run_time: Duration::ZERO,
+        verdicts: None,
+    };
+
+    let data = match read_input(solver, source) {
+        Ok(data) => data,
+        Err(error) => {
+            record.answers = Err(error);
+            return record;
+        }
+    };
+
+    // A panic in one solver should not abort the other days.
+    // The solvers are stateless, so nothing is left in a broken state after a panic.
     let timer = Instant::now();
-    let energized_tiles = day_16_the_floor_will_be_lava::determine_energized_tiles(file);
-
-    println!(
-        "Day 16: The Floor will be Lava\n\
-        Run Time: {:?}\n\
-        Energized Tiles 1: {}\n\
-        Energized Tiles 2: {}\n",
-        timer.elapsed(), energized_tiles.0, energized_tiles.1
-    );
+    let solve = || match observe::global_observer() {
+        Some(observer) => observe::observe(solver.day(), observer, || solver.solve(&data)),
+        None => solver.solve(&data)
+    };
+    match panic::catch_unwind(AssertUnwindSafe(solve)) {
+        Ok(Ok(solution)) => {
+            record.answers = Ok(solution.answers);
+            record.timings = solution.timings;
+        },
+        Ok(Err(error)) => record.answers = Err(error),
+        Err(_) => {}  // The record already holds the panic error.
+    }
+    record.run_time = timer.elapsed();
+
+    record
 }
 
-fn day_17() {
-    let file = Path::new("./data/day_17_clumsy_crucible.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let heat_loss = day_17_clumsy_crucible::determine_path_of_minimal_heat_loss(file);
-
-    println!(
-        "Day 17: Clumsy Crucible\n\
-        Run Time: {:?}\n\
-        Path of Minimum Heat Loss 1: {}\n\
-        Path of Minimum Heat Loss 2: {}\n",
-        timer.elapsed(), heat_loss.0, heat_loss.1
-    );
-}
 
-fn day_18() {
-    let file = Path::new("./data/day_18_lavaduct_lagoon.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let lagoon_volume =  day_18_lavaduct_lagoon::determine_lava_volume(file);
-
-    println!(
-        "Day 18: Lavaduct Lagoon\n\
-        Run Time: {:?}\n\
-        Lagoon Volume 1: {}\n\
-        Lagoon Volume 2: {}\n",
-        timer.elapsed(), lagoon_volume.0, lagoon_volume.1
-    );
+/// Solve the puzzles concurrently on the rayon thread pool, see [`run_day`].
+/// The records are returned in the same order as the solvers.
+#[cfg(all(feature = "fs", feature = "parallel"))]
+pub fn run_days_parallel(solvers: &[&dyn Solver], source: Source) -> Vec<Record> {
+    solvers
+        .par_iter()
+        .map(| &solver | run_day(solver, source))
+        .collect()
 }
 
-fn day_19() {
-    let file = Path::new("./data/day_19_aplenty.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (rating_numbers, distinct_combinations) =  day_19_aplenty::determine_rating_numbers(file);
-
-    println!(
-        "Day 19: Aplenty\n\
-        Run Time: {:?}\n\
-        Rating Numbers of Accepted Parts: {}\n\
-        Distinct Combinations of Ratings: {}\n",
-        timer.elapsed(), rating_numbers, distinct_combinations
-    );
-}
 
-fn day_20() {
-    let file = Path::new("./data/day_20_pulse_propagation.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (pulse_product, fewest_presses) =  day_20_pulse_propagation::determine_pulse_product(file);
-
-    println!(
-        "Day 20: Pulse Propagation\n\
-        Run Time: {:?}\n\
-        Pulse Product: {}\n\
-        Fewest Presses to reach RX: {}\n",
-        timer.elapsed(), pulse_product, fewest_presses
-    );
+/// Solve a single day and print the answers of the selected part(s) as text.
+/// The input is read from `file_path` if given, otherwise from the personal profile.
+/// Returns false if the day does not exist or the solver failed on the selected part(s) of its input.
+/// The reason of the failure is printed to stderr.
+#[cfg(feature = "fs")]
+pub fn solve_day(day: u8, file_path: Option<&str>, part: Part) -> bool {
+    let Some(solver) = solver(day) else {
+        eprintln!("Day {day} has no solution.\n");
+        return false;
+    };
+
+    let profile = Profile::new(inputs::PERSONAL);
+    let record = run_day(solver, Source::new(file_path, &profile));
+    output::print_record(&record, part, Format::Text);
+    !record.failed(part)
 }
 
-fn day_21() {
-    let file = Path::new("./data/day_21_step_counter.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let plots_visited =  day_21_step_counter::determine_visited_garden_plots(file);
-
-    println!(
-        "Day 21: Step Counter\n\
-        Run Time: {:?}\n\
-        Reachable Garden Plots 1: {}\n\
-        Reachable Garden Plots 2: {}\n",
-        timer.elapsed(), plots_visited.0, plots_visited.1
-    );
-}
 
-fn day_22() {
-    let file = Path::new("./data/day_22_sand_slabs.txt").to_str().unwrap();
-    // let file = Path::new("./data/test.txt").to_str().unwrap();
-    let timer = Instant::now();
-    let (safe_bricks, fallen_bricks) =  day_22_sand_slabs::determine_safe_bricks(file);
-
-    println!(
-        "Day 22: Sand Slabs\n\
-        Run Time: {:?}\n\
-        Safe Bricks: {}\n\
-        Fallen Bricks: {}\n",
-        timer.elapsed(), safe_bricks, fallen_bricks
-    );
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+
+    use super::*;
+
+    #[test]
+    fn test_registry_is_ordered_by_day() {
+        let days: Vec<u8> = SOLVERS.iter().map(| solver | solver.day()).collect();
+        assert!(days.is_sorted_by(| day, next_day | day < next_day));
+        assert!(days.iter().all(| day | DAYS.contains(day)));
+    }
+
+    #[test]
+    #[cfg(feature = "all_days")]
+    fn test_registry_has_every_day() {
+        assert!(DAYS.eq(SOLVERS.iter().map(| solver | solver.day())));
+    }
+
+    #[test]
+    fn test_failed_part() {
+        let mut record = Record {
+            day: 20,
+            title: "Pulse Propagation",
+            profile: None,
+            labels: ["", ""],
+            answers: Ok([Ok("32000000".to_string()), Err(Error::unsolvable("no rx"))]),
+            timings: Timings::default(),
+            run_time: Duration::ZERO,
+            verdicts: None,
+        };
+        assert!(!record.failed(Part::One));
+        assert!(record.failed(Part::Two));
+        assert!(record.failed(Part::Both));
+
+        record.verdicts = Some([Verdict::Fail { expected: "1".to_string() }, Verdict::Unknown]);
+        assert!(record.failed(Part::One));
+
+        record.answers = Err(Error::Panic);
+        record.verdicts = None;
+        assert!(record.failed(Part::One));
+    }
 }
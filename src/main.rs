@@ -1,5 +1,588 @@
-use advent_of_code_2023::solve_all;
+use std::fs;
+use std::io::{self, Write};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
-fn main() {
-    solve_all();
+use clap::{ArgGroup, Parser, Subcommand};
+
+use advent_of_code_2023::{DAYS, Part, Record, Source, generate_input, read_input, run_day, run_days_parallel, solver};
+use advent_of_code_2023::answers::Manifest;
+use advent_of_code_2023::bench::{self, Benchmark};
+use advent_of_code_2023::fetch::{self, Fetched, Fetcher, UreqHttp};
+use advent_of_code_2023::inputs::{self, Profile};
+use advent_of_code_2023::observe::{self, ProgressBar, TraceLog};
+use advent_of_code_2023::output::{self, Format};
+use advent_of_code_2023::render::{self, Frames};
+use advent_of_code_2023::solver::Solver;
+
+/// Solve the Advent of Code 2023 puzzles.
+#[derive(Debug, Parser)]
+#[command(version)]
+#[command(group(ArgGroup::new("drawing").args(["render", "animate"])))]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Solve a single day.
+    #[arg(long, conflicts_with = "days")]
+    day: Option<u8>,
+
+    /// Solve a range of days, e.g. "10-15".
+    #[arg(long, value_parser = parse_day_range)]
+    days: Option<RangeInclusive<u8>>,
+
+    /// Only report part 1 or part 2.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// Read the input from this file instead of "./data", use "-" for stdin.
+    #[arg(long, requires = "day")]
+    input: Option<String>,
+
+    /// Read the inputs from this profile: "personal" is "./data", any other profile is "./data/<NAME>".
+    #[arg(long, value_name = "NAME", value_parser = parse_profile, conflicts_with = "input")]
+    profile: Option<String>,
+
+    /// Solve the days for every profile in "./data".
+    #[arg(long, conflicts_with_all = ["input", "profile", "bench"])]
+    all_profiles: bool,
+
+    /// Output format: text, json (one object per line) or csv.
+    #[arg(long, default_value = "text")]
+    format: Format,
+
+    /// Check the answers against a TOML manifest of known answers,
+    /// defaults to the "answers.toml" of the profile if it has one.
+    #[arg(long, value_name = "FILE", conflicts_with = "bench")]
+    answers: Option<String>,
+
+    /// Solve the days concurrently, the output is still in day order.
+    #[arg(long, conflicts_with = "bench")]
+    parallel: bool,
+
+    /// Number of threads to use with --parallel, defaults to the number of CPUs.
+    #[arg(long, requires = "parallel")]
+    threads: Option<usize>,
+
+    /// Draw what the solver of --day does instead of solving it: ascii, ansi, ppm or png.
+    /// Draws part one unless --part 2 is given.
+    #[arg(long, value_name = "FORMAT", requires = "day", conflicts_with_all = ["all_profiles", "bench"])]
+    render: Option<render::Format>,
+
+    /// Draw the steps of the simulation of --day instead of solving it: ascii, ansi, ppm, png or apng.
+    /// Draws part one unless --part 2 is given.
+    #[arg(long, value_name = "FORMAT", requires = "day", conflicts_with_all = ["render", "all_profiles", "bench"])]
+    animate: Option<render::Format>,
+
+    /// The steps to draw with --animate, e.g. "0-100".
+    #[arg(long, value_parser = parse_step_range, default_value = "0-100")]
+    steps: RangeInclusive<usize>,
+
+    /// Draw every this many steps with --animate.
+    #[arg(long, value_parser = clap::value_parser!(u64).range(1..), default_value_t = 1)]
+    stride: u64,
+
+    /// Milliseconds between the frames of an animation in the terminal (ansi) or in an animated PNG.
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    frame_delay: u64,
+
+    /// Write the drawing to this file instead of stdout, required for the image formats.
+    /// With --animate each frame is written to a file in this directory, or all of them to this file for apng.
+    #[arg(long, value_name = "PATH", requires = "drawing")]
+    render_output: Option<String>,
+
+    /// Show the progress of the solvers on stderr.
+    #[arg(long, conflicts_with_all = ["trace", "bench"])]
+    progress: bool,
+
+    /// Log the phases, progress, intermediate values and steps of the solvers to stderr.
+    #[arg(long, conflicts_with = "bench")]
+    trace: bool,
+
+    /// Benchmark the days by solving each of them this many times.
+    #[arg(long, value_name = "RUNS", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// Number of unmeasured runs before benchmarking a day.
+    #[arg(long, value_name = "RUNS", default_value_t = 3, requires = "bench")]
+    warm_up: u32,
+
+    /// Save the benchmark results to this file.
+    #[arg(long, value_name = "FILE", requires = "bench")]
+    save_baseline: Option<String>,
+
+    /// Compare the benchmark results to a file saved with --save-baseline.
+    #[arg(long, value_name = "FILE", requires = "bench")]
+    baseline: Option<String>,
+
+    /// Flag a regression if a median run time is this many percent slower than the baseline.
+    #[arg(long, value_name = "PERCENT", default_value_t = 10.0, requires = "baseline")]
+    threshold: f64,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generate a random puzzle input, the same seed and size always generate the same input.
+    Generate {
+        /// Day of the puzzle.
+        #[arg(long)]
+        day: u8,
+
+        /// Seed of the random number generator.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+
+        /// Size of the input, e.g. the number of lines or the width of a grid depending on the day.
+        #[arg(long, value_parser = clap::value_parser!(u64).range(1..))]
+        size: u64,
+
+        /// Write the input to this file instead of stdout.
+        #[arg(long, value_name = "FILE", conflicts_with = "profile")]
+        output: Option<String>,
+
+        /// Write the input to its file in this profile, "./data/<NAME>".
+        #[arg(long, value_name = "NAME", value_parser = parse_profile)]
+        profile: Option<String>,
+    },
+
+    /// Download puzzle inputs from the website into a profile, inputs that are already there are kept.
+    /// The session token is read from $AOC_SESSION or from the file ".aoc_session".
+    Fetch {
+        /// Fetch a single day.
+        #[arg(long, conflicts_with = "days")]
+        day: Option<u8>,
+
+        /// Fetch a range of days, e.g. "10-15", defaults to every day.
+        #[arg(long, value_parser = parse_day_range)]
+        days: Option<RangeInclusive<u8>>,
+
+        /// Write the inputs to this profile: "personal" is "./data", any other profile is "./data/<NAME>".
+        #[arg(long, value_name = "NAME", value_parser = parse_profile, default_value = inputs::PERSONAL)]
+        profile: String,
+
+        /// Minimum number of seconds between two downloads.
+        #[arg(long, value_name = "SECONDS", default_value_t = fetch::DEFAULT_INTERVAL.as_secs())]
+        interval: u64,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if let Some(Command::Generate { day, seed, size, output, profile }) = &cli.command {
+        return match generate_day(*day, *seed, *size as usize, output.as_deref(), profile.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    if let Some(Command::Fetch { day, days, profile, interval }) = &cli.command {
+        let days = match (day, days) {
+            (Some(day), _) => *day..=*day,
+            (None, Some(days)) => days.clone(),
+            (None, None) => DAYS
+        };
+        return match fetch_days(days, &Profile::new(profile), Duration::from_secs(*interval)) {
+            0 => ExitCode::SUCCESS,
+            _ => ExitCode::FAILURE
+        };
+    }
+
+    let days = match (cli.day, cli.days.clone()) {
+        (Some(day), _) => day..=day,
+        (None, Some(days)) => days,
+        (None, None) => DAYS,
+    };
+
+    let part = match cli.part {
+        Some(1) => Part::One,
+        Some(2) => Part::Two,
+        _ => Part::Both
+    };
+
+    if cli.progress {
+        observe::set_global_observer(Some(Arc::new(ProgressBar::default())));
+    }
+    if cli.trace {
+        observe::set_global_observer(Some(Arc::new(TraceLog)));
+    }
+
+    let drawing = match (cli.render, cli.animate) {
+        (Some(format), _) => Some(render_day(&cli, *days.start(), part, format)),
+        (None, Some(format)) => Some(animate_day(&cli, *days.start(), part, format)),
+        (None, None) => None
+    };
+
+    let failures = match (cli.bench, drawing) {
+        (Some(runs), _) => bench_days(&cli, days, runs),
+        (None, Some(Ok(()))) => 0,
+        (None, Some(Err(error))) => {
+            eprintln!("{error}");
+            1
+        },
+        (None, None) => solve_days(&cli, days, part)
+    };
+
+    match failures {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE
+    }
+}
+
+/// Solve the days for the selected profiles and print the records.
+/// Returns the number of days that failed to solve or had a wrong answer.
+fn solve_days(cli: &Cli, days: RangeInclusive<u8>, part: Part) -> usize {
+    let manifest: Option<Manifest> = match cli.answers.as_deref().map(Manifest::load) {
+        Some(Ok(manifest)) => Some(manifest),
+        Some(Err(error)) => {
+            eprintln!("Answers: {error}");
+            return 1;
+        },
+        None => None
+    };
+
+    let profiles: Vec<Profile> = match cli.all_profiles {
+        true => match inputs::profiles() {
+            Ok(profiles) => profiles,
+            Err(error) => {
+                eprintln!("Profiles: {error}");
+                return 1;
+            }
+        },
+        false => vec![selected_profile(cli)]
+    };
+
+    let solvers: Vec<&dyn Solver> = days
+        .clone()
+        .filter_map(| day | {
+            let solver = solver(day);
+            if solver.is_none() {
+                eprintln!("Day {day} has no solution.\n");
+            }
+            solver
+        })
+        .collect();
+    let unknown_days = days.count() - solvers.len();
+
+    if let Some(header) = cli.format.header() {
+        println!("{header}");
+    }
+
+    let mut failures = unknown_days;
+    for profile in &profiles {
+        if cli.format == Format::Text && (cli.all_profiles || cli.profile.is_some()) {
+            println!("Profile: {}\n", profile.name());
+        }
+
+        // An input file is not part of the profile, so neither are its answers.
+        let profile_manifest = match (&manifest, profile.answers_path()) {
+            (Some(manifest), _) => Some(manifest.clone()),
+            (None, Some(answers_path)) if cli.input.is_none() => {
+                match Manifest::load(&answers_path.to_string_lossy()) {
+                    Ok(manifest) => Some(manifest),
+                    Err(error) => {
+                        eprintln!("Answers of profile {}: {error}\n", profile.name());
+                        failures += 1;
+                        None
+                    }
+                }
+            },
+            (None, _) => None
+        };
+
+        failures += solve_profile(cli, &solvers, Source::new(cli.input.as_deref(), profile), profile_manifest, part);
+    }
+    failures
+}
+
+/// Solve the days for one source of inputs, print the records and a summary.
+/// Returns the number of days that failed to solve or had a wrong answer.
+fn solve_profile(cli: &Cli, solvers: &[&dyn Solver], source: Source, manifest: Option<Manifest>, part: Part) -> usize {
+    let verify = | mut record: Record | {
+        record.verdicts = manifest.as_ref().map(| manifest | manifest.verify(&record));
+        record
+    };
+
+    let timer = Instant::now();
+    let records: Vec<Record> = match cli.parallel {
+        true => {
+            let pool = match rayon::ThreadPoolBuilder::new().num_threads(cli.threads.unwrap_or(0)).build() {
+                Ok(pool) => pool,
+                Err(error) => {
+                    eprintln!("Failed to start the thread pool: {error}");
+                    return solvers.len();
+                }
+            };
+            let records: Vec<Record> = pool
+                .install(|| run_days_parallel(solvers, source))
+                .into_iter()
+                .map(verify)
+                .collect();
+            records.iter().for_each(| record | output::print_record(record, part, cli.format));
+            records
+        },
+        false => solvers
+            .iter()
+            .map(| &solver | {
+                let record = verify(run_day(solver, source));
+                output::print_record(&record, part, cli.format);
+                record
+            })
+            .collect()
+    };
+    output::print_summary(&records, part, timer.elapsed(), cli.format);
+    if cli.format == Format::Text {
+        println!();
+    }
+
+    records.iter().filter(| record | record.failed(part)).count()
+}
+
+/// Benchmark the days and compare them to the baseline.
+/// Returns the number of days that failed or regressed.
+fn bench_days(cli: &Cli, days: RangeInclusive<u8>, runs: u32) -> usize {
+    let baseline: Vec<Benchmark> = match cli.baseline.as_deref().map(bench::load_baseline) {
+        Some(Ok(baseline)) => baseline,
+        Some(Err(error)) => {
+            eprintln!("Baseline: {error}");
+            return 1;
+        },
+        None => Vec::new()
+    };
+    let threshold = cli.threshold / 100.;
+    let profile = selected_profile(cli);
+
+    let mut failures: usize = 0;
+    let mut benchmarks: Vec<Benchmark> = Vec::new();
+
+    println!("Advent of Code 2023 ({} warm-up runs)\n", cli.warm_up);
+
+    for day in days {
+        let Some(solver) = solver(day) else {
+            eprintln!("Day {day} has no solution.\n");
+            failures += 1;
+            continue;
+        };
+
+        let benchmark = read_input(solver, Source::new(cli.input.as_deref(), &profile))
+            .and_then(| data | bench::benchmark(solver, &data, cli.warm_up as usize, runs as usize));
+        let benchmark = match benchmark {
+            Ok(benchmark) => benchmark,
+            Err(error) => {
+                eprintln!("Day {day}: {error}\n");
+                failures += 1;
+                continue;
+            }
+        };
+
+        let day_baseline = baseline.iter().find(| baseline | baseline.day == day);
+        if cli.baseline.is_some() && day_baseline.is_none() {
+            eprintln!("Day {day} is not in the baseline.");
+        }
+        if day_baseline.is_some_and(| baseline | !bench::regressions(&benchmark, baseline, threshold).is_empty()) {
+            failures += 1;
+        }
+
+        println!("{}", bench::report(&benchmark, day_baseline, threshold));
+        benchmarks.push(benchmark);
+    }
+
+    if let Some(file_path) = &cli.save_baseline {
+        if let Err(error) = bench::save_baseline(file_path, &benchmarks) {
+            eprintln!("Baseline: {error}");
+            failures += 1;
+        }
+    }
+
+    failures
+}
+
+/// Draw what the solver of a day does and write it to stdout or to the file given with --render-output.
+fn render_day(cli: &Cli, day: u8, part: Part, format: render::Format) -> Result<(), String> {
+    if format.is_image() && cli.render_output.is_none() {
+        return Err("Images are only written to a file, use --render-output.".to_string());
+    }
+    let Some(solver) = solver(day) else {
+        return Err(format!("Day {day} has no solution."));
+    };
+
+    let profile = selected_profile(cli);
+    let data = read_input(solver, Source::new(cli.input.as_deref(), &profile))
+        .map_err(| error | format!("Day {day}: {error}"))?;
+    let rendering = solver
+        .render(&data, part)
+        .ok_or_else(|| format!("Day {day} can not be rendered."))?
+        .and_then(| canvas | canvas.render(format))
+        .map_err(| error | format!("Day {day}: {error}"))?;
+
+    match &cli.render_output {
+        Some(file_path) => fs::write(file_path, rendering).map_err(| error | format!("{file_path}: {error}")),
+        None => io::stdout().write_all(&rendering).map_err(| error | error.to_string())
+    }
+}
+
+/// Draw the steps of the simulation of a day. The frames are written to stdout, or to a file per frame
+/// in the directory given with --render-output, or with apng all together to the file given with --render-output.
+fn animate_day(cli: &Cli, day: u8, part: Part, format: render::Format) -> Result<(), String> {
+    if format.is_image() && cli.render_output.is_none() {
+        return Err("Images are only written to a file, use --render-output.".to_string());
+    }
+    let Some(solver) = solver(day) else {
+        return Err(format!("Day {day} has no solution."));
+    };
+
+    let profile = selected_profile(cli);
+    let data = read_input(solver, Source::new(cli.input.as_deref(), &profile))
+        .map_err(| error | format!("Day {day}: {error}"))?;
+    let canvases = solver
+        .animate(&data, part, Frames::new(cli.steps.clone(), cli.stride as usize))
+        .ok_or_else(|| format!("Day {day} is not a simulation."))?
+        .map_err(| error | format!("Day {day}: {error}"))?;
+    let delay = Duration::from_millis(cli.frame_delay);
+
+    match (format, cli.render_output.as_deref()) {
+        (render::Format::Apng, Some(file_path)) => {
+            let animation = render::to_apng(&canvases, delay).map_err(| error | format!("Day {day}: {error}"))?;
+            fs::write(file_path, animation).map_err(| error | format!("{file_path}: {error}"))
+        },
+        (_, Some(dir)) => {
+            fs::create_dir_all(dir).map_err(| error | format!("{dir}: {error}"))?;
+            for (index, canvas) in canvases.iter().enumerate() {
+                let file_path = Path::new(dir).join(format!("frame_{index:05}.{}", format.extension()));
+                let frame = canvas.render(format).map_err(| error | format!("Day {day}: {error}"))?;
+                fs::write(&file_path, frame).map_err(| error | format!("{}: {error}", file_path.display()))?;
+            }
+            Ok(())
+        },
+        (_, None) => {
+            let mut stdout = io::stdout();
+            for (index, canvas) in canvases.iter().enumerate() {
+                // In the terminal each frame replaces the previous one, so that the frames play as an animation.
+                let separator = match (format, index) {
+                    (_, 0) => "",
+                    (render::Format::Ansi, _) => {
+                        thread::sleep(delay);
+                        "\x1b[H\x1b[2J"
+                    },
+                    _ => "\n"
+                };
+                let frame = canvas.render(format).map_err(| error | format!("Day {day}: {error}"))?;
+                stdout
+                    .write_all(separator.as_bytes())
+                    .and_then(| _ | stdout.write_all(&frame))
+                    .map_err(| error | error.to_string())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Generate the input of a day and write it to stdout, a file or the input file of a profile.
+fn generate_day(day: u8, seed: u64, size: usize, output: Option<&str>, profile: Option<&str>) -> Result<(), String> {
+    let Some(data) = generate_input(day, seed, size) else {
+        return Err(format!("Day {day} has no solution."));
+    };
+
+    let file_path = match (output, profile) {
+        (Some(file_path), _) => file_path.into(),
+        (None, Some(inputs::PERSONAL)) => return Err("Generated inputs are never written to the personal profile.".to_string()),
+        (None, Some(name)) => {
+            let profile = Profile::new(name);
+            fs::create_dir_all(profile.dir()).map_err(| error | format!("Profile {name}: {error}"))?;
+            profile.input_path(solver(day).unwrap())
+        },
+        (None, None) => {
+            print!("{data}");
+            return Ok(());
+        }
+    };
+    fs::write(&file_path, data).map_err(| error | format!("{}: {error}", file_path.display()))
+}
+
+/// Download the inputs of the days that are not in the profile yet.
+/// Returns the number of days that could not be fetched.
+fn fetch_days(days: RangeInclusive<u8>, profile: &Profile, interval: Duration) -> usize {
+    let solvers: Vec<&dyn Solver> = days
+        .clone()
+        .filter_map(| day | {
+            let solver = solver(day);
+            if solver.is_none() {
+                eprintln!("Day {day} has no solution.");
+            }
+            solver
+        })
+        .collect();
+    let mut failures = days.count() - solvers.len();
+
+    // The session token is only needed if there is something to download.
+    let missing = solvers.iter().any(| solver | !profile.input_path(*solver).is_file());
+    let session = match missing {
+        true => match fetch::session_token() {
+            Ok(session) => session,
+            Err(error) => {
+                eprintln!("{error}");
+                return failures + 1;
+            }
+        },
+        false => String::new()
+    };
+
+    let mut fetcher = Fetcher::new(UreqHttp::default(), fetch::BASE_URL, &session, interval);
+    for solver in solvers {
+        match fetcher.fetch(solver, profile) {
+            Ok(Fetched::Cached(file_path)) => println!("Day {}: {} is already there.", solver.day(), file_path.display()),
+            Ok(Fetched::Downloaded(file_path)) => println!("Day {}: downloaded {}.", solver.day(), file_path.display()),
+            Err(error) => {
+                eprintln!("Day {}: {error}", solver.day());
+                failures += 1;
+            }
+        }
+    }
+    failures
+}
+
+/// The profile given on the command line, or the personal profile.
+fn selected_profile(cli: &Cli) -> Profile {
+    Profile::new(cli.profile.as_deref().unwrap_or(inputs::PERSONAL))
+}
+
+/// Parse a profile name, which must be usable as a directory name.
+fn parse_profile(name: &str) -> Result<String, String> {
+    match inputs::valid_profile_name(name) {
+        true => Ok(name.to_string()),
+        false => Err(format!("invalid profile name \"{name}\", use letters, digits, '-', '_' and '.'"))
+    }
+}
+
+/// Parse a range of days like "10-15".
+fn parse_day_range(range: &str) -> Result<RangeInclusive<u8>, String> {
+    parse_range(range, "day")
+}
+
+/// Parse a range of steps like "0-100".
+fn parse_step_range(range: &str) -> Result<RangeInclusive<usize>, String> {
+    parse_range(range, "step")
+}
+
+/// Parse a non-empty range of numbers like "10-15", the name is used in the errors.
+fn parse_range<T: FromStr + PartialOrd>(range: &str, name: &str) -> Result<RangeInclusive<T>, String> {
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| format!("expected a range like \"10-15\", got \"{range}\""))?;
+
+    let start: T = start.trim().parse().map_err(| _ | format!("invalid {name} \"{start}\""))?;
+    let end: T = end.trim().parse().map_err(| _ | format!("invalid {name} \"{end}\""))?;
+
+    if start > end {
+        return Err(format!("range \"{range}\" is empty"));
+    }
+    Ok(start..=end)
 }
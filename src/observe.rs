@@ -0,0 +1,291 @@
+use std::cell::RefCell;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// A phase of solving a puzzle, see [`crate::solver::solve_timed`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Phase {
+    Parse,
+    PartOne,
+    PartTwo
+}
+
+impl Phase {
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::PartOne => "part one",
+            Phase::PartTwo => "part two"
+        }
+    }
+}
+
+/// Something that happened while solving a puzzle.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    /// A phase started.
+    Start(Phase),
+    /// A phase finished after the given time, successfully or not.
+    End(Phase, Duration),
+    /// `done` steps of a long loop have been taken, out of `total` if that is known in advance.
+    Progress { label: &'a str, done: usize, total: Option<usize> },
+    /// An intermediate value, e.g. the cycle found in a simulation.
+    Value { label: &'a str, value: String },
+    /// A single step of the solver, only sent to observers that want a trace.
+    Trace(String)
+}
+
+
+/// Receives the events of the solvers.
+/// The events of a day come from the thread that solves it, or from the rayon threads it uses.
+pub trait Observer: Send + Sync {
+    fn event(&self, day: u8, event: &Event);
+
+    /// Whether to send trace events, which are built for every step of a solver and slow it down.
+    fn wants_trace(&self) -> bool {
+        false
+    }
+}
+
+
+/// The observer of the day that is solved on the current thread, if any.
+/// Solvers that spread their work over rayon threads take the scope along with [`current`].
+#[derive(Clone, Default)]
+pub struct Scope {
+    day: u8,
+    observer: Option<Arc<dyn Observer>>
+}
+
+impl Scope {
+    /// True if an observer is attached, so events are worth building.
+    pub fn is_observed(&self) -> bool {
+        self.observer.is_some()
+    }
+
+    pub fn event(&self, event: &Event) {
+        if let Some(observer) = &self.observer {
+            observer.event(self.day, event);
+        }
+    }
+
+    pub fn progress(&self, label: &str, done: usize, total: Option<usize>) {
+        self.event(&Event::Progress { label, done, total });
+    }
+
+    pub fn value(&self, label: &str, value: impl Display) {
+        if self.is_observed() {
+            self.event(&Event::Value { label, value: value.to_string() });
+        }
+    }
+
+    /// The message is only built if the observer wants a trace.
+    pub fn trace(&self, message: impl FnOnce() -> String) {
+        if let Some(observer) = self.observer.as_ref().filter(| observer | observer.wants_trace()) {
+            observer.event(self.day, &Event::Trace(message()));
+        }
+    }
+}
+
+
+thread_local! {
+    static CURRENT: RefCell<Scope> = RefCell::default();
+}
+
+static GLOBAL: RwLock<Option<Arc<dyn Observer>>> = RwLock::new(None);
+
+/// Restores the previous scope of the thread, also when the solver panics.
+struct Restore(Scope);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let previous = std::mem::take(&mut self.0);
+        CURRENT.with(| current | current.replace(previous));
+    }
+}
+
+
+/// Run `f` with an observer attached to the current thread, which receives the events of the day.
+pub fn observe<T>(day: u8, observer: Arc<dyn Observer>, f: impl FnOnce() -> T) -> T {
+    let scope = Scope { day, observer: Some(observer) };
+    let _restore = Restore(CURRENT.with(| current | current.replace(scope)));
+    f()
+}
+
+/// The scope of the current thread, to pass the observer on to other threads.
+pub fn current() -> Scope {
+    CURRENT.with(| current | current.borrow().clone())
+}
+
+/// Set the observer that [`crate::run_day`] attaches to every day it solves, None to detach it.
+pub fn set_global_observer(observer: Option<Arc<dyn Observer>>) {
+    *GLOBAL.write().unwrap_or_else(| error | error.into_inner()) = observer;
+}
+
+/// The observer set with [`set_global_observer`].
+pub fn global_observer() -> Option<Arc<dyn Observer>> {
+    GLOBAL.read().unwrap_or_else(| error | error.into_inner()).clone()
+}
+
+
+/// Send an event to the observer of the current thread, if any.
+pub fn event(event: &Event) {
+    CURRENT.with(| current | current.borrow().event(event));
+}
+
+/// Report the progress of a long loop to the observer of the current thread, if any.
+pub fn progress(label: &str, done: usize, total: Option<usize>) {
+    CURRENT.with(| current | current.borrow().progress(label, done, total));
+}
+
+/// Report an intermediate value to the observer of the current thread, if any.
+pub fn value(label: &str, value: impl Display) {
+    CURRENT.with(| current | current.borrow().value(label, value));
+}
+
+/// Trace a step to the observer of the current thread, if it wants a trace.
+pub fn trace(message: impl FnOnce() -> String) {
+    CURRENT.with(| current | current.borrow().trace(message));
+}
+
+
+/// Shows the progress of the days on a single line of stderr, redrawn at most ten times per second.
+#[derive(Default)]
+pub struct ProgressBar {
+    last_draw: Mutex<Option<Instant>>
+}
+
+impl Observer for ProgressBar {
+    fn event(&self, day: u8, event: &Event) {
+        let line = match event {
+            Event::Start(phase) => format!("Day {day}: {}", phase.name()),
+            Event::Progress { label, done, total: Some(total) } if *total > 0 => {
+                format!("Day {day}: {label} {done}/{total} ({}%)", 100 * done / total)
+            },
+            Event::Progress { label, done, .. } => format!("Day {day}: {label} {done}"),
+            Event::End(..) => {
+                eprint!("\r\x1b[K");
+                return;
+            },
+            Event::Value { .. } | Event::Trace(_) => return
+        };
+
+        let mut last_draw = self.last_draw.lock().unwrap_or_else(| error | error.into_inner());
+        let redraw = matches!(event, Event::Start(_))
+            || last_draw.is_none_or(| last_draw | last_draw.elapsed() >= Duration::from_millis(100));
+        if redraw {
+            eprint!("\r\x1b[K{line}");
+            let _ = io::stderr().flush();
+            *last_draw = Some(Instant::now());
+        }
+    }
+}
+
+
+/// Writes every event to stderr, one line per event.
+pub struct TraceLog;
+
+impl Observer for TraceLog {
+    fn event(&self, day: u8, event: &Event) {
+        match event {
+            Event::Start(phase) => eprintln!("[day {day}] {} started", phase.name()),
+            Event::End(phase, time) => eprintln!("[day {day}] {} finished in {time:?}", phase.name()),
+            Event::Progress { label, done, total: Some(total) } => eprintln!("[day {day}] {label} {done}/{total}"),
+            Event::Progress { label, done, total: None } => eprintln!("[day {day}] {label} {done}"),
+            Event::Value { label, value } => eprintln!("[day {day}] {label}: {value}"),
+            Event::Trace(message) => eprintln!("[day {day}] {message}")
+        }
+    }
+
+    fn wants_trace(&self) -> bool {
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records the events as text.
+    #[derive(Default)]
+    struct Recorder {
+        events: Mutex<Vec<String>>,
+        trace: bool
+    }
+
+    impl Observer for Recorder {
+        fn event(&self, day: u8, event: &Event) {
+            self.events.lock().unwrap().push(format!("{day} {event:?}"));
+        }
+
+        fn wants_trace(&self) -> bool {
+            self.trace
+        }
+    }
+
+    #[test]
+    fn test_silent_without_observer() {
+        progress("steps", 1, None);
+        value("answer", 42);
+        trace(|| panic!("the trace message is built without an observer"));
+        assert!(!current().is_observed());
+    }
+
+    #[test]
+    fn test_events_of_the_scope() {
+        let recorder = Arc::new(Recorder::default());
+        let answer = observe(3, recorder.clone(), || {
+            progress("steps", 1, Some(2));
+            value("answer", 42);
+            trace(|| panic!("the trace message is built for an observer that does not want it"));
+            42
+        });
+        progress("steps", 2, Some(2));
+
+        assert_eq!(answer, 42);
+        assert_eq!(*recorder.events.lock().unwrap(), [
+            "3 Progress { label: \"steps\", done: 1, total: Some(2) }",
+            "3 Value { label: \"answer\", value: \"42\" }",
+        ]);
+    }
+
+    #[test]
+    fn test_trace_and_nested_scopes() {
+        let outer = Arc::new(Recorder { trace: true, ..Recorder::default() });
+        let inner = Arc::new(Recorder { trace: true, ..Recorder::default() });
+        observe(1, outer.clone(), || {
+            observe(2, inner.clone(), || trace(|| "inner".to_string()));
+            let scope = current();
+            std::thread::spawn(move || scope.trace(|| "thread".to_string())).join().unwrap();
+        });
+
+        assert_eq!(*outer.events.lock().unwrap(), ["1 Trace(\"thread\")"]);
+        assert_eq!(*inner.events.lock().unwrap(), ["2 Trace(\"inner\")"]);
+    }
+
+    #[test]
+    fn test_scope_is_restored_after_a_panic() {
+        let recorder = Arc::new(Recorder::default());
+        let result = std::panic::catch_unwind(|| observe(1, recorder.clone(), || panic!("solver bug")));
+        assert!(result.is_err());
+        assert!(!current().is_observed());
+    }
+
+    #[test]
+    #[cfg(feature = "day_6")]
+    fn test_phases_of_a_solver() {
+        let recorder = Arc::new(Recorder::default());
+        let data = "Time:      7  15   30\nDistance:  9  40  200\n";
+        observe(6, recorder.clone(), || crate::solver(6).unwrap().solve(data)).unwrap();
+
+        let events = recorder.events.lock().unwrap();
+        let phases: Vec<&str> = events
+            .iter()
+            .map(| event | event.split_once('(').unwrap().0)
+            .collect();
+        assert_eq!(phases, ["6 Start", "6 End", "6 Start", "6 End", "6 Start", "6 End"]);
+        assert!(events[4].ends_with("Start(PartTwo)"));
+    }
+}
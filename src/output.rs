@@ -0,0 +1,290 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+#[cfg(feature = "json")]
+use serde::Serialize;
+
+use crate::{Part, Record};
+use crate::answers::Verdict;
+
+/// Format in which the solve records are printed.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Format {
+    /// A human-readable block per day.
+    #[default]
+    Text,
+    /// One JSON object per day, one per line.
+    #[cfg(feature = "json")]
+    Json,
+    /// One CSV row per day, after a header row.
+    Csv
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Format::Text),
+            #[cfg(feature = "json")]
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format \"{format}\", expected text, json or csv"))
+        }
+    }
+}
+
+const CSV_HEADER: &str = "day,title,profile,\
+    part_one_label,part_one_answer,part_one_ns,part_one_verdict,\
+    part_two_label,part_two_answer,part_two_ns,part_two_verdict,\
+    parse_ns,run_time_ns,error";
+
+impl Format {
+    /// The lines to print before the first record.
+    pub fn header(self) -> Option<&'static str> {
+        match self {
+            Format::Text => Some("Advent of Code 2023\n"),
+            #[cfg(feature = "json")]
+            Format::Json => None,
+            Format::Csv => Some(CSV_HEADER)
+        }
+    }
+}
+
+
+#[cfg_attr(feature = "json", derive(Serialize))]
+struct PartRecord<'a> {
+    label: &'a str,
+    /// None if the part failed, the reason is in `error`.
+    answer: Option<&'a str>,
+    time_ns: u128,
+    verdict: Option<&'a str>,
+    expected: Option<&'a str>,
+    error: Option<String>,
+}
+
+#[cfg(feature = "json")]
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    day: u8,
+    title: &'a str,
+    profile: Option<&'a str>,
+    part_one: Option<PartRecord<'a>>,
+    part_two: Option<PartRecord<'a>>,
+    parse_ns: u128,
+    run_time_ns: u128,
+    error: Option<String>,
+}
+
+
+/// Print a record to stdout in the given format.
+/// In the text format a failure is printed to stderr instead, and so is the failure of a selected part.
+pub fn print_record(record: &Record, part: Part, format: Format) {
+    match (format, &record.answers) {
+        (Format::Text, Err(error)) => eprintln!("Day {}: {error}\n", record.day),
+        (Format::Text, Ok(_)) => {
+            println!("{}", to_text(record, part));
+            // A part that fails as the manifest expects passes, it has no error to report.
+            for (index, part) in selected_parts(record, part).into_iter().enumerate() {
+                if let Some(error) = part.filter(| part | part.verdict != Some("pass")).and_then(| part | part.error) {
+                    eprintln!("Day {} part {}: {error}\n", record.day, index + 1);
+                }
+            }
+        },
+        #[cfg(feature = "json")]
+        (Format::Json, _) => println!("{}", to_json(record, part)),
+        (Format::Csv, _) => println!("{}", to_csv(record, part))
+    }
+}
+
+/// Print the total wall-clock time of a run and the summed run time of its days,
+/// and the number of verified answers of the selected part(s) if they were checked.
+/// The summary goes to stderr for the machine-readable formats, so that stdout only has records.
+pub fn print_summary(records: &[Record], part: Part, wall_time: Duration, format: Format) {
+    let cpu_time: Duration = records.iter().map(| record | record.run_time).sum();
+    let mut summary = format!("Total Wall Time: {wall_time:?}\nTotal CPU Time: {cpu_time:?} (sum of all days)");
+
+    let verdicts: Vec<&Verdict> = records
+        .iter()
+        .filter_map(| record | record.verdicts.as_ref())
+        .flat_map(| verdicts | match part {
+            Part::One => &verdicts[..1],
+            Part::Two => &verdicts[1..],
+            Part::Both => &verdicts[..]
+        })
+        .collect();
+
+    if !verdicts.is_empty() {
+        let count = | name: &str | verdicts.iter().filter(| verdict | verdict.name() == name).count();
+        summary.push_str(&format!(
+            "\nVerified: {} passed, {} failed, {} unknown",
+            count("pass"), count("fail"), count("unknown")
+        ));
+    }
+
+    match format {
+        Format::Text => println!("{summary}"),
+        #[cfg(feature = "json")]
+        Format::Json => eprintln!("{summary}"),
+        Format::Csv => eprintln!("{summary}")
+    }
+}
+
+/// The labels and answers of the selected parts, None for parts that are not selected or if the day failed.
+fn selected_parts(record: &Record, part: Part) -> [Option<PartRecord<'_>>; 2] {
+    let Ok(answers) = &record.answers else {
+        return [None, None];
+    };
+
+    let times = [record.timings.part_one, record.timings.part_two];
+    let select = | index: usize | PartRecord {
+        label: record.labels[index],
+        answer: answers[index].as_deref().ok(),
+        time_ns: times[index].as_nanos(),
+        verdict: record.verdicts.as_ref().map(| verdicts | verdicts[index].name()),
+        expected: record.verdicts.as_ref().and_then(| verdicts | verdicts[index].expected()),
+        error: answers[index].as_ref().err().map(| error | error.to_string()),
+    };
+    match part {
+        Part::One => [Some(select(0)), None],
+        Part::Two => [None, Some(select(1))],
+        Part::Both => [Some(select(0)), Some(select(1))]
+    }
+}
+
+fn to_text(record: &Record, part: Part) -> String {
+    let timings = &record.timings;
+    let mut text = format!(
+        "Day {}: {}\nRun Time: {:?} (parse: {:?}, part 1: {:?}, part 2: {:?})\n",
+        record.day, record.title, record.run_time, timings.parse, timings.part_one, timings.part_two
+    );
+    for part in selected_parts(record, part).into_iter().flatten() {
+        let verdict = match (part.verdict, part.expected) {
+            (Some(_), Some(expected)) => format!(" (FAIL, expected {expected})"),
+            (Some(verdict), None) => format!(" ({verdict})"),
+            (None, _) => String::new()
+        };
+        text.push_str(&format!("{}: {}{verdict}\n", part.label, part.answer.unwrap_or("no answer")));
+    }
+    text
+}
+
+#[cfg(feature = "json")]
+fn to_json(record: &Record, part: Part) -> String {
+    let [part_one, part_two] = selected_parts(record, part);
+    let json = JsonRecord {
+        day: record.day,
+        title: record.title,
+        profile: record.profile.as_deref(),
+        part_one,
+        part_two,
+        parse_ns: record.timings.parse.as_nanos(),
+        run_time_ns: record.run_time.as_nanos(),
+        error: record.answers.as_ref().err().map(| error | error.to_string()),
+    };
+    serde_json::to_string(&json).expect("a record always serializes")
+}
+
+fn to_csv(record: &Record, part: Part) -> String {
+    let [part_one, part_two] = selected_parts(record, part);
+    let part_fields = | part: &Option<PartRecord> | match part {
+        Some(part) => [
+            part.label.to_string(),
+            part.answer.unwrap_or_default().to_string(),
+            part.time_ns.to_string(),
+            part.verdict.unwrap_or_default().to_string()
+        ],
+        None => Default::default()
+    };
+
+    // The error of the day, or else the errors of the selected parts that failed.
+    let error = match &record.answers {
+        Err(error) => error.to_string(),
+        Ok(_) => [&part_one, &part_two]
+            .into_iter()
+            .enumerate()
+            .filter_map(| (index, part) | Some(format!("part {}: {}", index + 1, part.as_ref()?.error.as_ref()?)))
+            .collect::<Vec<String>>()
+            .join("; ")
+    };
+
+    [record.day.to_string(), record.title.to_string(), record.profile.clone().unwrap_or_default()]
+        .into_iter()
+        .chain(part_fields(&part_one))
+        .chain(part_fields(&part_two))
+        .chain([
+            record.timings.parse.as_nanos().to_string(),
+            record.run_time.as_nanos().to_string(),
+            error
+        ])
+        .map(| field | csv_field(&field))
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::solver::{Answers, Timings};
+
+    use super::*;
+
+    fn cube_record(answers: crate::error::Result<Answers>) -> Record {
+        Record {
+            day: 2,
+            title: "Cube Conundrum",
+            profile: Some("example".to_string()),
+            labels: ["Sum of IDs", "Sum of powers"],
+            answers,
+            timings: Timings {
+                parse: Duration::from_nanos(600),
+                part_one: Duration::from_nanos(300),
+                part_two: Duration::from_nanos(400),
+            },
+            run_time: Duration::from_nanos(1500),
+            verdicts: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "json")]
+    fn test_json() {
+        let record = cube_record(Ok([Ok("8".to_string()), Ok("2286".to_string())]));
+        assert_eq!(
+            to_json(&record, Part::Two),
+            "{\"day\":2,\"title\":\"Cube Conundrum\",\"profile\":\"example\",\"part_one\":null,\
+            \"part_two\":{\"label\":\"Sum of powers\",\"answer\":\"2286\",\"time_ns\":400,\
+            \"verdict\":null,\"expected\":null,\"error\":null},\
+            \"parse_ns\":600,\"run_time_ns\":1500,\"error\":null}"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let record = cube_record(Ok([Ok("8".to_string()), Ok("2286".to_string())]));
+        assert_eq!(to_csv(&record, Part::Both), "2,Cube Conundrum,example,Sum of IDs,8,300,,Sum of powers,2286,400,,600,1500,");
+
+        let record = cube_record(Err(Error::parse(0, 18, "unknown color \"purple\"")));
+        assert_eq!(
+            to_csv(&record, Part::Both),
+            "2,Cube Conundrum,example,,,,,,,,,600,1500,\"line 1, column 19: unknown color \"\"purple\"\"\""
+        );
+
+        let record = cube_record(Ok([Ok("8".to_string()), Err(Error::unsolvable("no game"))]));
+        assert_eq!(
+            to_csv(&record, Part::Both),
+            "2,Cube Conundrum,example,Sum of IDs,8,300,,Sum of powers,,400,,600,1500,part 2: no solution: no game"
+        );
+        assert_eq!(to_csv(&record, Part::One), "2,Cube Conundrum,example,Sum of IDs,8,300,,,,,,600,1500,");
+    }
+}
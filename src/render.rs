@@ -0,0 +1,333 @@
+use std::io;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::util::grid::Grid;
+
+/// Width and height in pixels of a tile in the images.
+const TILE_PIXELS: usize = 4;
+
+/// Format in which a canvas is rendered.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Format {
+    /// The symbols of the tiles and the caption as plain text.
+    Ascii,
+    /// The symbols coloured by their style with ANSI escape codes, for a terminal.
+    Ansi,
+    /// A binary PPM image with a square of pixels per tile.
+    Ppm,
+    /// A PNG image with a square of pixels per tile.
+    Png,
+    /// An animated PNG with a frame per canvas.
+    Apng
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> std::result::Result<Self, Self::Err> {
+        match format {
+            "ascii" => Ok(Format::Ascii),
+            "ansi" => Ok(Format::Ansi),
+            "ppm" => Ok(Format::Ppm),
+            "png" => Ok(Format::Png),
+            "apng" => Ok(Format::Apng),
+            _ => Err(format!("unknown render format \"{format}\", expected ascii, ansi, ppm, png or apng"))
+        }
+    }
+}
+
+impl Format {
+    /// True for the formats that are images rather than text.
+    pub fn is_image(self) -> bool {
+        matches!(self, Format::Ppm | Format::Png | Format::Apng)
+    }
+
+    /// Extension of the files in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ascii | Format::Ansi => "txt",
+            Format::Ppm => "ppm",
+            Format::Png | Format::Apng => "png"
+        }
+    }
+}
+
+
+/// The steps of a simulation to draw: every `stride` steps from the first step up to the last, both included.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Frames {
+    pub first: usize,
+    pub last: usize,
+    pub stride: usize
+}
+
+impl Frames {
+    /// # Panics
+    /// If the stride is zero.
+    pub fn new(steps: RangeInclusive<usize>, stride: usize) -> Self {
+        assert!(stride > 0, "the stride of the frames must be at least one");
+        Self { first: *steps.start(), last: *steps.end(), stride }
+    }
+
+    /// True if the step is drawn.
+    pub fn contains(&self, step: usize) -> bool {
+        (self.first..=self.last).contains(&step) && (step - self.first).is_multiple_of(self.stride)
+    }
+}
+
+
+/// How a tile stands out from the rest.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Style {
+    /// A tile of the puzzle input that the solver did nothing with.
+    #[default]
+    Plain,
+    /// A tile in the background, e.g. empty space.
+    Faint,
+    /// The main result of the solver, e.g. the path it found.
+    Primary,
+    /// A second result, e.g. the tiles enclosed by a loop.
+    Secondary,
+    /// A single tile of interest, e.g. the start.
+    Accent
+}
+
+impl Style {
+    fn ansi_code(self) -> &'static str {
+        match self {
+            Style::Plain => "\x1b[0m",
+            Style::Faint => "\x1b[0;2m",
+            Style::Primary => "\x1b[0;1;33m",
+            Style::Secondary => "\x1b[0;1;36m",
+            Style::Accent => "\x1b[0;1;31m"
+        }
+    }
+
+    fn color(self, symbol: char) -> [u8; 3] {
+        match self {
+            Style::Plain if symbol == '.' => [20, 20, 20],
+            Style::Plain => [150, 150, 150],
+            Style::Faint => [60, 60, 60],
+            Style::Primary => [255, 200, 0],
+            Style::Secondary => [0, 190, 220],
+            Style::Accent => [230, 40, 40]
+        }
+    }
+}
+
+
+/// A tile of a canvas, its symbol in text and its style.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Tile {
+    pub symbol: char,
+    pub style: Style
+}
+
+impl Tile {
+    pub fn new(symbol: char, style: Style) -> Self {
+        Self { symbol, style }
+    }
+}
+
+
+/// A picture of what a solver did, with a tile per position of the puzzle grid.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    pub tiles: Grid<Tile>,
+    /// What the picture shows, e.g. the answer and the meaning of the symbols.
+    pub caption: String
+}
+
+impl Canvas {
+    pub fn new(tiles: Grid<Tile>, caption: impl Into<String>) -> Self {
+        Self { tiles, caption: caption.into() }
+    }
+
+    /// Number of tiles with the given style.
+    pub fn count(&self, style: Style) -> usize {
+        self.tiles.iter().filter(| (_, tile) | tile.style == style).count()
+    }
+
+    /// The symbols of the tiles and the caption below them.
+    pub fn to_ascii(&self) -> String {
+        self.tiles.to_text(| tile | tile.symbol) + &self.caption + "\n"
+    }
+
+    /// The symbols coloured by their style, and the caption below them.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        for row in self.tiles.rows() {
+            let mut style: Option<Style> = None;
+            for tile in row {
+                if style != Some(tile.style) {
+                    text += tile.style.ansi_code();
+                    style = Some(tile.style);
+                }
+                text.push(tile.symbol);
+            }
+            text += Style::Plain.ansi_code();
+            text.push('\n');
+        }
+        text + &self.caption + "\n"
+    }
+
+    /// The colours of the pixels row by row as RGB, with a square of pixels per tile.
+    /// Returns the width and height of the image and the pixels.
+    pub fn to_pixels(&self) -> (usize, usize, Vec<u8>) {
+        let (width, height) = (self.tiles.width() * TILE_PIXELS, self.tiles.height() * TILE_PIXELS);
+        let mut pixels: Vec<u8> = Vec::with_capacity(3 * width * height);
+        for row in self.tiles.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(| tile | [tile.style.color(tile.symbol); TILE_PIXELS])
+                .flatten()
+                .collect();
+            for _ in 0..TILE_PIXELS {
+                pixels.extend(&line);
+            }
+        }
+        (width, height, pixels)
+    }
+
+    /// A binary PPM image of the tiles, without the caption.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let (width, height, pixels) = self.to_pixels();
+        let mut image = format!("P6\n{width} {height}\n255\n").into_bytes();
+        image.extend(pixels);
+        image
+    }
+
+    /// A PNG image of the tiles, with the caption as its description.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let (width, height, pixels) = self.to_pixels();
+        let mut image: Vec<u8> = Vec::new();
+
+        let mut encoder = png::Encoder::new(&mut image, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.add_text_chunk("Description".to_string(), self.caption.clone()).map_err(png_error)?;
+        let mut writer = encoder.write_header().map_err(png_error)?;
+        writer.write_image_data(&pixels).map_err(png_error)?;
+        writer.finish().map_err(png_error)?;
+
+        Ok(image)
+    }
+
+    /// Render the canvas in a format.
+    pub fn render(&self, format: Format) -> Result<Vec<u8>> {
+        match format {
+            Format::Ascii => Ok(self.to_ascii().into_bytes()),
+            Format::Ansi => Ok(self.to_ansi().into_bytes()),
+            Format::Ppm => Ok(self.to_ppm()),
+            Format::Png => self.to_png(),
+            Format::Apng => to_apng(std::slice::from_ref(self), Duration::ZERO)
+        }
+    }
+}
+
+
+/// An animated PNG with a frame per canvas, which loops forever.
+/// The canvases must have the same size, the caption of the first one is the description.
+pub fn to_apng(frames: &[Canvas], delay: Duration) -> Result<Vec<u8>> {
+    let Some(first) = frames.first() else {
+        return Err(Error::Io(io::Error::other("an animation needs at least one frame")));
+    };
+    if frames.iter().any(| frame | frame.tiles.width() != first.tiles.width() || frame.tiles.height() != first.tiles.height()) {
+        return Err(Error::Io(io::Error::other("the frames of an animation must have the same size")));
+    }
+
+    let (width, height, _) = first.to_pixels();
+    let mut image: Vec<u8> = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut image, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.add_text_chunk("Description".to_string(), first.caption.clone()).map_err(png_error)?;
+    encoder.set_animated(frames.len() as u32, 0).map_err(png_error)?;
+    encoder.set_frame_delay(delay.as_millis().min(u16::MAX as u128) as u16, 1000).map_err(png_error)?;
+
+    let mut writer = encoder.write_header().map_err(png_error)?;
+    for frame in frames {
+        writer.write_image_data(&frame.to_pixels().2).map_err(png_error)?;
+    }
+    writer.finish().map_err(png_error)?;
+
+    Ok(image)
+}
+
+fn png_error(error: png::EncodingError) -> Error {
+    Error::Io(io::Error::other(error))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let tiles = Grid::new(3, 2, vec![
+            Tile::new('S', Style::Accent), Tile::new('>', Style::Primary), Tile::new('v', Style::Primary),
+            Tile::new('.', Style::Faint), Tile::new('#', Style::Plain), Tile::new('E', Style::Accent),
+        ]);
+        Canvas::new(tiles, "a path")
+    }
+
+    #[test]
+    fn test_text() {
+        let canvas = canvas();
+        assert_eq!(canvas.to_ascii(), "S>v\n.#E\na path\n");
+        assert_eq!(
+            canvas.to_ansi(),
+            "\x1b[0;1;31mS\x1b[0;1;33m>v\x1b[0m\n\x1b[0;2m.\x1b[0m#\x1b[0;1;31mE\x1b[0m\na path\n"
+        );
+        assert_eq!(canvas.count(Style::Primary), 2);
+    }
+
+    #[test]
+    fn test_images() {
+        let canvas = canvas();
+        let (width, height, pixels) = canvas.to_pixels();
+        assert_eq!((width, height, pixels.len()), (3 * TILE_PIXELS, 2 * TILE_PIXELS, 3 * width * height));
+        assert_eq!(pixels[..3], Style::Accent.color('S'));
+        assert_eq!(pixels[3 * width * (height - 1)..][..3], Style::Faint.color('.'));
+
+        let ppm = canvas.to_ppm();
+        assert!(ppm.starts_with(b"P6\n12 8\n255\n"));
+        assert!(ppm.ends_with(&pixels));
+
+        let png = canvas.to_png().unwrap();
+        let mut reader = png::Decoder::new(io::Cursor::new(png)).read_info().unwrap();
+        let mut decoded: Vec<u8> = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut decoded).unwrap();
+        assert_eq!(decoded, pixels);
+    }
+
+    #[test]
+    fn test_animation() {
+        let frames = [canvas(), canvas()];
+        let apng = to_apng(&frames, Duration::from_millis(100)).unwrap();
+        let reader = png::Decoder::new(io::Cursor::new(apng)).read_info().unwrap();
+        assert_eq!(reader.info().animation_control().map(| control | control.num_frames), Some(2));
+
+        let small = Canvas::new(Grid::new(1, 1, vec![Tile::new('.', Style::Faint)]), "");
+        assert!(to_apng(&[canvas(), small], Duration::ZERO).is_err());
+        assert!(to_apng(&[], Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn test_frames() {
+        let frames = Frames::new(2..=8, 3);
+        let steps: Vec<usize> = (0..12).filter(| &step | frames.contains(step)).collect();
+        assert_eq!(steps, [2, 5, 8]);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!("png".parse(), Ok(Format::Png));
+        assert!("gif".parse::<Format>().is_err());
+        assert!(Format::Ppm.is_image() && !Format::Ansi.is_image());
+    }
+}
@@ -0,0 +1,126 @@
+use std::borrow::Borrow;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+#[cfg(feature = "render")]
+use crate::Part;
+use crate::error::Result;
+use crate::observe::{self, Event, Phase};
+#[cfg(feature = "render")]
+use crate::render::{Canvas, Frames};
+use crate::util::random::Rng;
+
+/// The answers to part one and part two of a puzzle, a part that failed has its own error.
+pub type Answers = [Result<String>; 2];
+
+/// Time spent on each phase of solving a puzzle.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct Timings {
+    pub parse: Duration,
+    pub part_one: Duration,
+    pub part_two: Duration,
+}
+
+impl Timings {
+    /// Time spent on all phases together.
+    pub fn total(&self) -> Duration {
+        self.parse + self.part_one + self.part_two
+    }
+}
+
+/// The answers to a puzzle and the time it took to find them.
+#[derive(Debug)]
+pub struct Solution {
+    pub answers: Answers,
+    pub timings: Timings,
+}
+
+impl Solution {
+    /// The answers to both parts, or the error of the first part that failed.
+    pub fn into_answers(self) -> Result<[String; 2]> {
+        let [answer_one, answer_two] = self.answers;
+        Ok([answer_one?, answer_two?])
+    }
+}
+
+/// Common interface of the daily puzzles.
+/// Each day implements this on a unit struct, which is listed in [`crate::SOLVERS`].
+pub trait Solver: Sync {
+    /// Day of the puzzle in the advent calendar.
+    fn day(&self) -> u8;
+
+    /// Title of the puzzle.
+    fn title(&self) -> &'static str;
+
+    /// Descriptions of the answers to part one and part two.
+    fn labels(&self) -> [&'static str; 2];
+
+    /// Name of the puzzle input file in `./data`.
+    fn input_file(&self) -> &'static str;
+
+    /// Solve both parts of the puzzle for the puzzle input text.
+    fn solve(&self, data: &str) -> Result<Solution>;
+
+    /// Generate a random puzzle input that the solver can solve.
+    /// What the size measures depends on the puzzle, e.g. the number of lines or the width of a grid.
+    /// The size must be at least one.
+    fn generate(&self, rng: &mut Rng, size: usize) -> String;
+
+    /// Draw what the solver does for part one, or for part two if `part` is [`Part::Two`].
+    /// None if the puzzle has no grid to draw.
+    #[cfg(feature = "render")]
+    fn render(&self, _data: &str, _part: Part) -> Option<Result<Canvas>> {
+        None
+    }
+
+    /// Draw the selected steps of the simulation of part one, or of part two if `part` is [`Part::Two`].
+    /// None if the puzzle is not a simulation.
+    #[cfg(feature = "render")]
+    fn animate(&self, _data: &str, _part: Part, _frames: Frames) -> Option<Result<Vec<Canvas>>> {
+        None
+    }
+}
+
+
+/// Solve a puzzle in three timed phases:
+/// parse the input text, then solve part one and part two from the parsed input.
+/// The parts may take a borrowed form of the parsed input, e.g. a slice of a parsed `Vec`.
+/// Only a parse error fails the whole puzzle, part two is solved even if part one failed.
+pub fn solve_timed<'a, I, P, A, B>(
+    data: &'a str,
+    parse: impl FnOnce(&'a str) -> Result<I>,
+    part_one: impl FnOnce(&P) -> Result<A>,
+    part_two: impl FnOnce(&P) -> Result<B>,
+) -> Result<Solution>
+where
+    I: Borrow<P>,
+    P: ?Sized,
+    A: ToString,
+    B: ToString,
+{
+    let (input, parse_time) = timed_phase(Phase::Parse, || parse(data));
+    let input = input?;
+
+    let (answer_one, part_one_time) = timed_phase(Phase::PartOne, || Ok(part_one(input.borrow())?.to_string()));
+    let (answer_two, part_two_time) = timed_phase(Phase::PartTwo, || Ok(part_two(input.borrow())?.to_string()));
+
+    Ok(Solution {
+        answers: [answer_one, answer_two],
+        timings: Timings { parse: parse_time, part_one: part_one_time, part_two: part_two_time },
+    })
+}
+
+
+/// Run a phase and measure its time, the observer is told when the phase starts and ends.
+fn timed_phase<T>(phase: Phase, f: impl FnOnce() -> Result<T>) -> (Result<T>, Duration) {
+    observe::event(&Event::Start(phase));
+    let timer = Instant::now();
+    let output = f();
+    let time = timer.elapsed();
+    observe::event(&Event::End(phase, time));
+    (output, time)
+}
@@ -0,0 +1,118 @@
+/// A simulation whose states repeat: after a prefix of steps, the states loop with a fixed length.
+/// Found with Brent's algorithm, so only a few states are kept in memory however long the cycle is.
+pub struct Cycle<S, F> {
+    start: S,
+    /// The state after `prefix` steps, the first state of the loop.
+    cycle_start: S,
+    step: F,
+    prefix: usize,
+    length: usize
+}
+
+impl<S: Clone + Eq, F: FnMut(&S) -> S> Cycle<S, F> {
+    /// Find the cycle of the states reached by repeatedly applying `step` to `start`.
+    /// The states must eventually repeat, which holds for every simulation with a finite number of states.
+    pub fn find(start: S, mut step: F) -> Self {
+        // Find the length: the hare runs ahead, the tortoise jumps to the hare at every power of two.
+        let mut power: usize = 1;
+        let mut length: usize = 1;
+        let mut tortoise = start.clone();
+        let mut hare = step(&start);
+        while tortoise != hare {
+            if power == length {
+                tortoise = hare.clone();
+                power *= 2;
+                length = 0;
+            }
+            hare = step(&hare);
+            length += 1;
+        }
+
+        // Find the prefix: with the hare one length ahead, they first meet at the start of the loop.
+        let mut tortoise = start.clone();
+        let mut hare = start.clone();
+        for _ in 0..length {
+            hare = step(&hare);
+        }
+
+        let mut prefix: usize = 0;
+        while tortoise != hare {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            prefix += 1;
+        }
+
+        Self { start, cycle_start: tortoise, step, prefix, length }
+    }
+
+    /// Number of steps before the states start to loop.
+    pub fn prefix(&self) -> usize {
+        self.prefix
+    }
+
+    /// Number of steps in the loop.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// The first step with the same state as the target step, always less than `prefix + length`.
+    pub fn equivalent_step(&self, target: usize) -> usize {
+        match target.checked_sub(self.prefix) {
+            Some(steps_in_loop) => self.prefix + steps_in_loop % self.length,
+            None => target
+        }
+    }
+
+    /// The state after `target` steps, found in less than `prefix + length` steps.
+    pub fn state_at(&mut self, target: usize) -> S {
+        let (mut state, n_steps) = match target.checked_sub(self.prefix) {
+            Some(steps_in_loop) => (self.cycle_start.clone(), steps_in_loop % self.length),
+            None => (self.start.clone(), target)
+        };
+
+        for _ in 0..n_steps {
+            state = (self.step)(&state);
+        }
+        state
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        // 0 -> 1 -> 2 -> 5 -> 26 -> 677 -> 160 -> ... loops modulo 1000.
+        let step = | &x: &u32 | (x * x + 1) % 1000;
+
+        let mut history: Vec<u32> = vec![0];
+        while !history[..history.len() - 1].contains(history.last().unwrap()) {
+            history.push(step(history.last().unwrap()));
+        }
+        let repeated = history.pop().unwrap();
+        let prefix = history.iter().position(| &x | x == repeated).unwrap();
+
+        let mut cycle = Cycle::find(0, step);
+        assert_eq!(cycle.prefix(), prefix);
+        assert_eq!(cycle.length(), history.len() - prefix);
+
+        for target in [0, 1, prefix, prefix + 1, 1_000, 1_000_000_000] {
+            let expected = history[cycle.equivalent_step(target)];
+            assert_eq!(cycle.state_at(target), expected);
+        }
+        assert_eq!(cycle.state_at(3), 5);
+    }
+
+    #[test]
+    fn test_cycle_without_prefix() {
+        let mut cycle = Cycle::find(3_u8, | &x | (x + 1) % 7);
+        assert_eq!((cycle.prefix(), cycle.length()), (0, 7));
+        assert_eq!(cycle.state_at(1_000_000_000), ((3 + 1_000_000_000) % 7) as u8);
+
+        let mut fixed_point = Cycle::find("still", | &state | state);
+        assert_eq!((fixed_point.prefix(), fixed_point.length()), (0, 1));
+        assert_eq!(fixed_point.state_at(42), "still");
+    }
+}
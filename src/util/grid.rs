@@ -0,0 +1,293 @@
+use std::ops::{Index, IndexMut};
+
+use crate::error::{Error, Result};
+use crate::util;
+
+/// A position in a grid as (x, y), with (0, 0) the top-left tile.
+pub type Position = (usize, usize);
+
+/// Steps to the neighbours north, east, south and west.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Steps to the orthogonal and the diagonal neighbours, clockwise from north.
+pub const ALL_DIRECTIONS: [(isize, isize); 8] = [(0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1)];
+
+/// A rectangular grid of tiles, stored row by row.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize
+}
+
+impl<T> Grid<T> {
+    /// Create a grid from its tiles in row-major order.
+    ///
+    /// # Panics
+    /// If the number of tiles is not `width * height`.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "a {width}x{height} grid needs {} tiles", width * height);
+        Self { cells, width, height }
+    }
+
+    /// Create a grid with the tile at each position given by a function.
+    pub fn from_fn(width: usize, height: usize, mut tile: impl FnMut(Position) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(| y | (0..width).map(move | x | (x, y)))
+            .map(&mut tile)
+            .collect();
+        Self { cells, width, height }
+    }
+
+    /// Create a grid with every tile set to the same value.
+    pub fn filled(width: usize, height: usize, value: T) -> Self where T: Clone {
+        Self { cells: vec![value; width * height], width, height }
+    }
+
+    /// Parse a non-empty rectangular grid with a tile per character.
+    /// `tile` converts a character to a tile, or returns None for an unknown symbol.
+    pub fn parse(data: &str, mut tile: impl FnMut(char) -> Option<T>) -> Result<Self> {
+        let mut cells: Vec<T> = Vec::with_capacity(data.len());
+        let mut width: usize = 0;
+        let mut height: usize = 0;
+
+        for line in util::lines(data) {
+            let row_start = cells.len();
+            for (column, character) in line.text.char_indices() {
+                cells.push(tile(character).ok_or_else(|| line.unknown_symbol(&line.text[column..]))?);
+            }
+
+            let row_width = cells.len() - row_start;
+            if line.index == 0 {
+                width = row_width;
+            } else if row_width != width {
+                return Err(line.error(line.text, format!("expected a row of {width} tiles")));
+            }
+            height += 1;
+        }
+
+        if width == 0 {
+            return Err(Error::parse(0, 0, "expected a grid of at least one tile"));
+        }
+        Ok(Self { cells, width, height })
+    }
+
+    /// Convert the grid to text with a character per tile and a line per row.
+    pub fn to_text(&self, mut tile: impl FnMut(&T) -> char) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            text.extend(row.iter().map(&mut tile));
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The tile at a position, None if the position is outside of the grid.
+    pub fn get(&self, (x, y): Position) -> Option<&T> {
+        if x < self.width && y < self.height {
+            Some(&self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The tile at a position, None if the position is outside of the grid.
+    pub fn get_mut(&mut self, (x, y): Position) -> Option<&mut T> {
+        if x < self.width && y < self.height {
+            Some(&mut self.cells[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    /// The tile at a position in the plane that is tiled with copies of the grid in every direction.
+    ///
+    /// # Panics
+    /// If the grid has no tiles.
+    pub fn get_wrapped(&self, (x, y): (isize, isize)) -> &T {
+        let x = x.rem_euclid(self.width as isize) as usize;
+        let y = y.rem_euclid(self.height as isize) as usize;
+        &self.cells[y * self.width + x]
+    }
+
+    /// The position one step of `(dx, dy)` away, None if it is outside of the grid.
+    pub fn step(&self, (x, y): Position, (dx, dy): (isize, isize)) -> Option<Position> {
+        let x = x.checked_add_signed(dx).filter(| &x | x < self.width)?;
+        let y = y.checked_add_signed(dy).filter(| &y | y < self.height)?;
+        Some((x, y))
+    }
+
+    /// The positions north, east, south and west of a position that are in the grid.
+    pub fn neighbours(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ORTHOGONAL.into_iter().filter_map(move | direction | self.step(position, direction))
+    }
+
+    /// The positions around a position that are in the grid, including the diagonal ones.
+    pub fn neighbours_with_diagonals(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        ALL_DIRECTIONS.into_iter().filter_map(move | direction | self.step(position, direction))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move | y | (0..width).map(move | x | (x, y)))
+    }
+
+    /// All tiles with their position in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The position of the first tile in row-major order that matches the predicate.
+    pub fn position(&self, predicate: impl FnMut(&T) -> bool) -> Option<Position> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(| index | (index % self.width, index / self.width))
+    }
+
+    /// A row of tiles from west to east.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    /// The rows from north to south, none if the grid has no columns.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // A grid without columns has no tiles, so any chunk size gives no rows.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [T]> {
+        self.cells.chunks_mut(self.width.max(1))
+    }
+
+    /// A column of tiles from north to south.
+    ///
+    /// # Panics
+    /// If `x` is outside of the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside of a grid of width {}", self.width);
+        self.cells[x..].iter().step_by(self.width)
+    }
+
+    /// The columns from west to east.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(| x | self.column(x))
+    }
+
+    /// A grid of the same size with a function applied to every tile.
+    pub fn map<U>(&self, tile: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { cells: self.cells.iter().map(tile).collect(), width: self.width, height: self.height }
+    }
+
+    /// Mirror the grid in its diagonal, the rows become the columns.
+    pub fn transpose(&self) -> Self where T: Clone {
+        Self::from_fn(self.height, self.width, | (x, y) | self[(y, x)].clone())
+    }
+
+    /// Rotate the grid a quarter turn clockwise, the west column becomes the north row.
+    pub fn rotate_clockwise(&self) -> Self where T: Clone {
+        Self::from_fn(self.height, self.width, | (x, y) | self[(y, self.height - 1 - x)].clone())
+    }
+
+    /// Rotate the grid a quarter turn anti-clockwise, the east column becomes the north row.
+    pub fn rotate_anti_clockwise(&self) -> Self where T: Clone {
+        Self::from_fn(self.height, self.width, | (x, y) | self[(self.width - 1 - y, x)].clone())
+    }
+}
+
+impl Grid<u8> {
+    /// Parse a grid of ASCII tiles that only contains the given symbols.
+    pub fn from_symbols(data: &str, symbols: &str) -> Result<Self> {
+        Self::parse(data, | character | match symbols.contains(character) && character.is_ascii() {
+            true => Some(character as u8),
+            false => None
+        })
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): Position) -> &T {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside of a {}x{} grid", self.width, self.height);
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (x, y): Position) -> &mut T {
+        assert!(x < self.width && y < self.height, "({x}, {y}) is outside of a {}x{} grid", self.width, self.height);
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str = "ab\ncd\nef\n";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::from_symbols(TEXT, "abcdef").unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 2)], b'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_text(| &tile | tile as char), TEXT);
+
+        let error = Grid::from_symbols("ab\nc\n", "abc").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 1: expected a row of 2 tiles");
+        assert!(Grid::from_symbols("ab\nax\n", "abc").is_err());
+        assert!(Grid::from_symbols("", "abc").is_err());
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours((0, 0)).collect::<Vec<_>>(), [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_with_diagonals((2, 2)).collect::<Vec<_>>(), [(2, 1), (1, 2), (1, 1)]);
+        assert_eq!(grid.neighbours_with_diagonals((1, 1)).count(), 8);
+        assert_eq!(grid.step((2, 0), (-2, 2)), Some((0, 2)));
+        assert_eq!(grid.step((2, 0), (0, -1)), None);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = Grid::from_symbols(TEXT, "abcdef").unwrap();
+        assert_eq!(grid.row(1), b"cd");
+        assert_eq!(grid.column(1).copied().collect::<Vec<u8>>(), b"bdf");
+        assert_eq!(grid.columns().count(), 2);
+        assert_eq!(grid.position(| &tile | tile == b'e'), Some((0, 2)));
+        assert_eq!(*grid.get_wrapped((-1, 4)), b'd');
+
+        let mut empty: Grid<u8> = Grid::filled(0, 3, b'.');
+        assert_eq!(empty.rows().count(), 0);
+        assert_eq!(empty.rows_mut().count(), 0);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let grid = Grid::from_symbols(TEXT, "abcdef").unwrap();
+        let text = | grid: &Grid<u8> | grid.to_text(| &tile | tile as char);
+
+        assert_eq!(text(&grid.transpose()), "ace\nbdf\n");
+        assert_eq!(text(&grid.rotate_clockwise()), "eca\nfdb\n");
+        assert_eq!(text(&grid.rotate_anti_clockwise()), "bdf\nace\n");
+        assert_eq!(grid.rotate_clockwise().rotate_anti_clockwise(), grid);
+    }
+}
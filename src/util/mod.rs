@@ -0,0 +1,64 @@
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+pub mod cycle;
+pub mod grid;
+pub mod number_theory;
+pub mod polynomial;
+pub mod random;
+#[cfg(feature = "search")]
+pub mod search;
+
+
+/// Iterate over the lines of the puzzle input together with their index.
+pub fn lines(data: &str) -> impl Iterator<Item = Line<'_>> {
+    data.lines().enumerate().map(| (index, text) | Line { index, text })
+}
+
+
+/// A line of the puzzle input, used to report errors at the right position.
+/// Every `part` passed to the methods must be a slice of `text`, otherwise the column falls back to 0.
+#[derive(Debug, Clone, Copy)]
+pub struct Line<'a> {
+    pub index: usize,
+    pub text: &'a str
+}
+
+impl<'a> Line<'a> {
+    /// Column at which `part` starts within this line.
+    pub fn column(&self, part: &str) -> usize {
+        (part.as_ptr() as usize)
+            .checked_sub(self.text.as_ptr() as usize)
+            .filter(| &column | column <= self.text.len())
+            .unwrap_or(0)
+    }
+
+    /// Parse error located at the start of `part`.
+    pub fn error(&self, part: &str, message: impl Into<String>) -> Error {
+        Error::parse(self.index, self.column(part), message)
+    }
+
+    /// Unknown symbol error for the first character of `part`.
+    pub fn unknown_symbol(&self, part: &str) -> Error {
+        match part.chars().next() {
+            Some(symbol) => Error::unknown_symbol(self.index, self.column(part), symbol),
+            None => self.error(part, "unexpected end of line")
+        }
+    }
+
+    /// Split `part` at the first occurrence of `delimiter`.
+    pub fn split_once(&self, part: &'a str, delimiter: &str) -> Result<(&'a str, &'a str)> {
+        part
+            .split_once(delimiter)
+            .ok_or_else(|| self.error(part, format!("expected {delimiter:?}")))
+    }
+
+    /// Parse `part` as a number.
+    pub fn parse<T: FromStr>(&self, part: &str) -> Result<T> {
+        part
+            .parse::<T>()
+            .map_err(| _ | self.error(part, format!("invalid number {part:?}")))
+    }
+}
+
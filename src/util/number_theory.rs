@@ -0,0 +1,189 @@
+/// Greatest common divisor, `gcd(0, 0)` is 0.
+pub fn gcd(a: usize, b: usize) -> usize {
+    // usize is at most 128 bits wide, so the conversions are lossless.
+    gcd_u128(a as u128, b as u128) as usize
+}
+
+/// Greatest common divisor of two `u128`, `gcd_u128(0, 0)` is 0.
+pub fn gcd_u128(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+
+/// Least common multiple, None if it overflows. The lcm with 0 is 0.
+pub fn checked_lcm(a: usize, b: usize) -> Option<usize> {
+    checked_lcm_u128(a as u128, b as u128).and_then(| lcm | usize::try_from(lcm).ok())
+}
+
+/// Least common multiple of two `u128`, None if it overflows. The lcm with 0 is 0.
+pub fn checked_lcm_u128(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 { return Some(0); }
+    (a / gcd_u128(a, b)).checked_mul(b)
+}
+
+/// Least common multiple of all the values, None if it overflows.
+/// The lcm of no values is 1.
+pub fn lcm(nums: &[usize]) -> Option<usize> {
+    nums.iter().try_fold(1, | lcm, &num | checked_lcm(lcm, num))
+}
+
+/// Least common multiple of all the `u128` values, None if it overflows.
+/// The lcm of no values is 1.
+pub fn lcm_u128(nums: &[u128]) -> Option<u128> {
+    nums.iter().try_fold(1, | lcm, &num | checked_lcm_u128(lcm, num))
+}
+
+
+/// Extended Euclidean algorithm.
+/// Returns `(g, x, y)` with `a * x + b * y == g`, where `g` is the non-negative gcd of `a` and `b`.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+
+    match old_r < 0 {
+        true => (-old_r, -old_x, -old_y),
+        false => (old_r, old_x, old_y)
+    }
+}
+
+
+/// The inverse of `a` modulo `modulus`, i.e. the `x` in `0..modulus` with `a * x ≡ 1`.
+/// None if `a` and `modulus` are not coprime, if `modulus` is zero or if a value does not fit in an `i128`.
+pub fn mod_inverse(a: u128, modulus: u128) -> Option<u128> {
+    let m = i128::try_from(modulus).ok().filter(| &m | m > 0)?;
+    let a = i128::try_from(a % modulus).ok()?;
+
+    match extended_gcd(a, m) {
+        (1, x, _) => Some(x.rem_euclid(m) as u128),
+        _ => None
+    }
+}
+
+
+/// The values `x` with `x ≡ residue (mod modulus)`.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Congruence {
+    pub residue: u128,
+    pub modulus: u128
+}
+
+impl Congruence {
+    /// The congruence class of `value`, None if `modulus` is zero.
+    pub fn new(value: u128, modulus: u128) -> Option<Self> {
+        Some(Self { residue: value.checked_rem(modulus)?, modulus })
+    }
+
+    /// Smallest value of the congruence that is at least `min`, None if it overflows.
+    pub fn smallest_at_least(&self, min: u128) -> Option<u128> {
+        match min.checked_sub(self.residue) {
+            None | Some(0) => Some(self.residue),
+            Some(distance) => {
+                let n_periods = distance.div_ceil(self.modulus);
+                n_periods.checked_mul(self.modulus)?.checked_add(self.residue)
+            }
+        }
+    }
+}
+
+
+/// Chinese Remainder Theorem, the moduli do not have to be coprime.
+/// Combines the congruences into one whose modulus is the lcm of their moduli.
+/// None if the congruences have no common solution, if a modulus is zero or if a computation overflows.
+/// No congruences combine to the congruence of all values, `0 (mod 1)`.
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence { residue: 0, modulus: 1 }, combine)
+}
+
+fn combine(a: Congruence, b: Congruence) -> Option<Congruence> {
+    // Solve x = a.residue + a.modulus * k for k with x ≡ b.residue (mod b.modulus).
+    let b = Congruence::new(b.residue, b.modulus)?;
+    let g = gcd_u128(a.modulus, b.modulus);
+
+    // Work with the difference modulo b.modulus to stay within unsigned integers.
+    let a_residue = a.residue % b.modulus;
+    let difference = match b.residue.checked_sub(a_residue) {
+        Some(difference) => difference,
+        None => b.modulus - a_residue + b.residue
+    };
+    if !difference.is_multiple_of(g) { return None; }
+
+    let reduced_modulus = b.modulus / g;
+    let k = match reduced_modulus {
+        1 => 0,
+        _ => (difference / g)
+            .checked_mul(mod_inverse(a.modulus / g, reduced_modulus)?)?
+            % reduced_modulus
+    };
+
+    let modulus = (a.modulus / g).checked_mul(b.modulus)?;
+    let residue = a.modulus.checked_mul(k)?.checked_add(a.residue)? % modulus;
+    Some(Congruence { residue, modulus })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(&[4, 6, 10]), Some(60));
+        assert_eq!(lcm(&[]), Some(1));
+        assert_eq!(lcm(&[usize::MAX, usize::MAX - 1]), None);
+        assert_eq!(lcm_u128(&[u64::MAX as u128, u64::MAX as u128 - 1]), Some(u64::MAX as u128 * (u64::MAX as u128 - 1)));
+    }
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        for (a, b) in [(240, 46), (-240, 46), (17, 0), (0, -5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs() as usize, b.unsigned_abs() as usize) as i128);
+            assert_eq!(a * x + b * y, g);
+        }
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(4, 8), None);
+        assert_eq!(mod_inverse(4, 0), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        let congruence = | value, modulus | Congruence::new(value, modulus).unwrap();
+
+        // Coprime moduli.
+        let result = crt([congruence(2, 3), congruence(3, 5), congruence(2, 7)]);
+        assert_eq!(result, Some(congruence(23, 105)));
+
+        // Moduli with a common factor, with and without a solution.
+        assert_eq!(crt([congruence(3, 4), congruence(1, 6)]), Some(congruence(7, 12)));
+        assert_eq!(crt([congruence(0, 4), congruence(1, 6)]), None);
+
+        assert_eq!(crt([]), Some(congruence(0, 1)));
+        assert_eq!(Congruence::new(1, 0), None);
+    }
+
+    #[test]
+    fn test_smallest_at_least() {
+        let congruence = Congruence::new(3, 10).unwrap();
+        assert_eq!(congruence.smallest_at_least(0), Some(3));
+        assert_eq!(congruence.smallest_at_least(3), Some(3));
+        assert_eq!(congruence.smallest_at_least(4), Some(13));
+        assert_eq!(congruence.smallest_at_least(23), Some(23));
+    }
+}
@@ -0,0 +1,95 @@
+/// A polynomial with integer values at the integers, in Newton's forward difference form:
+/// `p(x) = d_0 + d_1 * C(x, 1) + d_2 * C(x, 2) + ...` with `C(x, k)` the binomial coefficient.
+/// All arithmetic is exact, so it can be evaluated far from the samples without rounding errors.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Polynomial {
+    /// The leading differences `d_k`, without trailing zeros.
+    differences: Vec<i128>
+}
+
+impl Polynomial {
+    /// The polynomial of lowest degree through the samples at x = 0, 1, 2, ...
+    /// None if a difference overflows.
+    pub fn fit(samples: &[i128]) -> Option<Self> {
+        let mut differences: Vec<i128> = Vec::with_capacity(samples.len());
+        let mut row: Vec<i128> = samples.to_vec();
+
+        while let Some(&first) = row.first() {
+            differences.push(first);
+            row = row
+                .windows(2)
+                .map(| pair | pair[1].checked_sub(pair[0]))
+                .collect::<Option<_>>()?;
+        }
+
+        while differences.last() == Some(&0) {
+            differences.pop();
+        }
+        Some(Self { differences })
+    }
+
+    /// Degree of the polynomial, None for the zero polynomial.
+    pub fn degree(&self) -> Option<usize> {
+        self.differences.len().checked_sub(1)
+    }
+
+    /// The leading forward differences of the samples, i.e. the coefficients in Newton's form.
+    pub fn differences(&self) -> &[i128] {
+        &self.differences
+    }
+
+    /// Value of the polynomial at `x`, None if it overflows.
+    /// Negative `x` extrapolates before the first sample.
+    pub fn evaluate(&self, x: i128) -> Option<i128> {
+        let mut value: i128 = 0;
+        let mut binomial: i128 = 1;  // C(x, k)
+
+        for (k, &difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                // C(x, k) = C(x, k - 1) * (x - k + 1) / k, the division is exact.
+                let k = k as i128;
+                binomial = binomial.checked_mul(x.checked_sub(k - 1)?)? / k;
+            }
+            value = value.checked_add(difference.checked_mul(binomial)?)?;
+        }
+        Some(value)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_and_evaluate() {
+        let cubic = | x: i128 | 2 * x * x * x - 5 * x * x + 7;
+        let samples: Vec<i128> = (0..6).map(cubic).collect();
+
+        let polynomial = Polynomial::fit(&samples).unwrap();
+        assert_eq!(polynomial.degree(), Some(3));
+        for x in [-10, -1, 0, 5, 6, 1_000, 1_000_000] {
+            assert_eq!(polynomial.evaluate(x), Some(cubic(x)));
+        }
+
+        // The values of x(x - 1) / 2 are integers, but its coefficients are not.
+        let triangle = Polynomial::fit(&[0, 0, 1, 3]).unwrap();
+        assert_eq!(triangle.differences(), [0, 0, 1]);
+        assert_eq!(triangle.evaluate(202_300), Some(202_300 * 202_299 / 2));
+    }
+
+    #[test]
+    fn test_degenerate_polynomials() {
+        let zero = Polynomial::fit(&[]).unwrap();
+        assert_eq!(zero.degree(), None);
+        assert_eq!(zero.evaluate(12), Some(0));
+
+        let constant = Polynomial::fit(&[4, 4, 4]).unwrap();
+        assert_eq!(constant.degree(), Some(0));
+        assert_eq!(constant.evaluate(-3), Some(4));
+
+        assert_eq!(Polynomial::fit(&[i128::MIN, i128::MAX]), None);
+        assert_eq!(Polynomial::fit(&[0, 1]).unwrap().evaluate(i128::MAX), Some(i128::MAX));
+        assert_eq!(Polynomial::fit(&[0, 0, 2]).unwrap().evaluate(i128::MAX), None);
+    }
+}
@@ -0,0 +1,71 @@
+use std::ops::Range;
+
+/// A small seeded pseudo-random number generator (SplitMix64) for generating puzzle inputs.
+/// It is part of the crate so that a seed generates the same input with every build.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in the range, which must not be empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        self.range_u64(range.start as u64..range.end as u64) as usize
+    }
+
+    /// A number in the range, which must not be empty, for numbers that do not fit in a 32-bit `usize`.
+    pub fn range_u64(&mut self, range: Range<u64>) -> u64 {
+        assert!(!range.is_empty(), "cannot pick a number from an empty range");
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// True with the given probability.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < probability
+    }
+
+    /// A random element of a slice, which must not be empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+
+    /// A random byte of a string, which must not be empty.
+    pub fn pick_char(&mut self, symbols: &str) -> char {
+        *self.pick(symbols.as_bytes()) as char
+    }
+
+    /// Shuffle a slice in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.range(0..i + 1));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let numbers = | seed | { let mut rng = Rng::new(seed); (0..5).map(| _ | rng.range(0..1000)).collect::<Vec<_>>() };
+        assert_eq!(numbers(7), numbers(7));
+        assert_ne!(numbers(7), numbers(8));
+
+        let mut rng = Rng::new(0);
+        assert!((0..1000).map(| _ | rng.range(3..5)).all(| n | (3..5).contains(&n)));
+    }
+}
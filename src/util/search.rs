@@ -0,0 +1,316 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use indexmap::IndexMap;
+use indexmap::map::Entry;
+
+/// Cost of a step in a weighted search, the default value is the zero cost.
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+/// Parent index of the start state.
+const NO_PARENT: usize = usize::MAX;
+
+
+/// Counters of the work done by a search.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct SearchStats {
+    /// States whose successors were generated.
+    pub expanded: usize,
+    /// Distinct states that were reached.
+    pub discovered: usize,
+    /// Largest number of states waiting in the queue at once.
+    pub max_frontier: usize,
+}
+
+
+/// The states reached by a search, with their cost from the start and the state they were reached from.
+#[derive(Debug, Clone)]
+pub struct Reached<S, C> {
+    nodes: IndexMap<S, (usize, C)>
+}
+
+impl<S: Eq + Hash + Clone, C: Copy> Reached<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Self { nodes: IndexMap::from([(start, (NO_PARENT, cost))]) }
+    }
+
+    /// Cost of the cheapest known path to a state, None if the state was not reached.
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.nodes.get(state).map(| &(_, cost) | cost)
+    }
+
+    /// The cheapest known path from the start to a state, both included.
+    pub fn path(&self, state: &S) -> Option<Vec<S>> {
+        let mut index = self.nodes.get_index_of(state)?;
+        let mut path: Vec<S> = Vec::new();
+        while index != NO_PARENT {
+            let (state, &(parent, _)) = self.nodes.get_index(index).expect("a parent is always reached");
+            path.push(state.clone());
+            index = parent;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// All reached states with their cost, in the order they were reached.
+    pub fn iter(&self) -> impl Iterator<Item = (&S, C)> {
+        self.nodes.iter().map(| (state, &(_, cost)) | (state, cost))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+
+/// Breadth-first search from a start state, where every step costs one.
+/// Iterating yields each reachable state once with its distance, in order of distance,
+/// so the search stops as soon as the iterator is no longer advanced.
+pub struct Bfs<S, F> {
+    successors: F,
+    queue: VecDeque<usize>,
+    reached: Reached<S, usize>,
+    stats: SearchStats,
+}
+
+impl<S, F, I> Bfs<S, F>
+where
+    S: Eq + Hash + Clone,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    pub fn new(start: S, successors: F) -> Self {
+        Self {
+            successors,
+            queue: VecDeque::from([0]),
+            reached: Reached::new(start, 0),
+            stats: SearchStats { discovered: 1, max_frontier: 1, ..SearchStats::default() },
+        }
+    }
+
+    /// The states reached so far, their distances are final.
+    pub fn reached(&self) -> &Reached<S, usize> {
+        &self.reached
+    }
+
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
+impl<S, F, I> Iterator for Bfs<S, F>
+where
+    S: Eq + Hash + Clone,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    type Item = (S, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.queue.pop_front()?;
+        let (state, &(_, distance)) = self.reached.nodes.get_index(index).expect("queued states are reached");
+        let state = state.clone();
+
+        for next in (self.successors)(&state) {
+            if let Entry::Vacant(entry) = self.reached.nodes.entry(next) {
+                self.queue.push_back(entry.index());
+                entry.insert((index, distance + 1));
+            }
+        }
+        self.stats.expanded += 1;
+        self.stats.discovered = self.reached.len();
+        self.stats.max_frontier = self.stats.max_frontier.max(self.queue.len());
+
+        Some((state, distance))
+    }
+}
+
+
+/// Best-first search from a start state over weighted steps, Dijkstra's algorithm or A*.
+/// Iterating yields each reachable state once with the cost of its cheapest path, if the heuristic is consistent.
+/// Without a heuristic the states come in order of cost.
+pub struct Dijkstra<S, C, F, H> {
+    successors: F,
+    heuristic: H,
+    queue: BinaryHeap<(Reverse<C>, Reverse<C>, usize)>,
+    reached: Reached<S, C>,
+    done: Vec<bool>,
+    stats: SearchStats,
+}
+
+impl<S, C, F, I> Dijkstra<S, C, F, fn(&S) -> C>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    /// Dijkstra's algorithm, `successors` gives the next states with the cost of the step to each.
+    pub fn new(start: S, successors: F) -> Self {
+        Self::with_heuristic(start, successors, | _ | C::default())
+    }
+}
+
+impl<S, C, F, I, H> Dijkstra<S, C, F, H>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+{
+    /// A* search, `heuristic` estimates the remaining cost to the goal from a state.
+    /// The heuristic must be consistent: zero at the goal, and the estimate of a state is at most
+    /// the cost of a step plus the estimate of the next state. Then the first goal state found has the cheapest path.
+    /// A yielded state is never reopened, so a heuristic that only never overestimates is not enough.
+    pub fn with_heuristic(start: S, successors: F, mut heuristic: H) -> Self {
+        let estimate = heuristic(&start);
+        Self {
+            successors,
+            heuristic,
+            queue: BinaryHeap::from([(Reverse(estimate), Reverse(C::default()), 0)]),
+            reached: Reached::new(start, C::default()),
+            done: vec![false],
+            stats: SearchStats { discovered: 1, max_frontier: 1, ..SearchStats::default() },
+        }
+    }
+
+    /// The states reached so far, their costs are only final for the states that were yielded.
+    pub fn reached(&self) -> &Reached<S, C> {
+        &self.reached
+    }
+
+    pub fn stats(&self) -> SearchStats {
+        self.stats
+    }
+}
+
+impl<S, C, F, I, H> Iterator for Dijkstra<S, C, F, H>
+where
+    S: Eq + Hash + Clone,
+    C: Cost,
+    F: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    H: FnMut(&S) -> C,
+{
+    type Item = (S, C);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (_, Reverse(cost), index) = self.queue.pop()?;
+            if self.done[index] {
+                continue;  // A cheaper path to this state was already expanded.
+            }
+            self.done[index] = true;
+
+            let state = self.reached.nodes.get_index(index).expect("queued states are reached").0.clone();
+            for (next, step) in (self.successors)(&state) {
+                let next_cost = cost + step;
+                let next_index = match self.reached.nodes.entry(next) {
+                    Entry::Vacant(entry) => {
+                        let next_index = entry.index();
+                        entry.insert((index, next_cost));
+                        self.done.push(false);
+                        next_index
+                    },
+                    Entry::Occupied(mut entry) if !self.done[entry.index()] && next_cost < entry.get().1 => {
+                        entry.insert((index, next_cost));
+                        entry.index()
+                    },
+                    Entry::Occupied(_) => continue
+                };
+
+                let estimate = next_cost + (self.heuristic)(self.reached.nodes.get_index(next_index).unwrap().0);
+                self.queue.push((Reverse(estimate), Reverse(next_cost), next_index));
+            }
+            self.stats.expanded += 1;
+            self.stats.discovered = self.reached.len();
+            self.stats.max_frontier = self.stats.max_frontier.max(self.queue.len());
+
+            return Some((state, cost));
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::util::grid::{Grid, Position};
+
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.####.#.
+......#G
+";
+
+    fn open_neighbours(maze: &Grid<u8>) -> impl FnMut(&Position) -> Vec<Position> + '_ {
+        | &position | maze.neighbours(position).filter(| &next | maze[next] != b'#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = Grid::from_symbols(MAZE, "SG.#").unwrap();
+        let goal = maze.position(| &tile | tile == b'G').unwrap();
+
+        let mut search = Bfs::new((0, 0), open_neighbours(&maze));
+        assert_eq!(search.find(| &(position, _) | position == goal), Some((goal, 15)));
+
+        let path = search.reached().path(&goal).unwrap();
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert!(path.windows(2).all(| step | step[0].0.abs_diff(step[1].0) + step[0].1.abs_diff(step[1].1) == 1));
+
+        // Every open tile is reachable.
+        let open_tiles = maze.iter().filter(| (_, &tile) | tile != b'#').count();
+        assert_eq!(Bfs::new((0, 0), open_neighbours(&maze)).count(), open_tiles);
+        assert_eq!(Bfs::new((0, 0), open_neighbours(&maze)).take_while(| &(_, distance) | distance <= 2).count(), 4);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // The direct edge is more expensive than the detour.
+        let edges = | &node: &u8 | -> Vec<(u8, u32)> {
+            match node {
+                0 => vec![(1, 7), (2, 2)],
+                2 => vec![(3, 2)],
+                3 => vec![(1, 1)],
+                _ => vec![]
+            }
+        };
+
+        let mut search = Dijkstra::new(0, edges);
+        assert_eq!(search.find(| &(node, _) | node == 1), Some((1, 5)));
+        assert_eq!(search.reached().path(&1), Some(vec![0, 2, 3, 1]));
+
+        let costs: Vec<(u8, u32)> = Dijkstra::new(0, edges).collect();
+        assert_eq!(costs, [(0, 0), (2, 2), (3, 4), (1, 5)]);
+    }
+
+    #[test]
+    fn test_a_star() {
+        let maze = Grid::from_symbols(MAZE, "SG.#").unwrap();
+        let goal = maze.position(| &tile | tile == b'G').unwrap();
+        let successors = | &position: &Position | -> Vec<(Position, usize)> {
+            maze.neighbours(position).filter(| &next | maze[next] != b'#').map(| next | (next, 1)).collect()
+        };
+        let manhattan = | &(x, y): &Position | x.abs_diff(goal.0) + y.abs_diff(goal.1);
+
+        let mut dijkstra = Dijkstra::new((0, 0), successors);
+        let mut a_star = Dijkstra::with_heuristic((0, 0), successors, manhattan);
+        assert_eq!(dijkstra.find(| &(position, _) | position == goal), Some((goal, 15)));
+        assert_eq!(a_star.find(| &(position, _) | position == goal), Some((goal, 15)));
+        assert!(a_star.stats().expanded <= dijkstra.stats().expanded);
+    }
+}
@@ -0,0 +1,99 @@
+use wasm_bindgen::prelude::*;
+
+use crate::SOLVERS;
+use crate::error::Error;
+use crate::solver::Solver;
+
+/// A puzzle in the registry.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Puzzle {
+    pub day: u8,
+    pub title: String,
+    /// What the answers of part one and part two are.
+    pub labels: Vec<String>,
+    /// The file name of the puzzle input in a profile.
+    pub input_file: String
+}
+
+/// The answers to a puzzle and the time spent on each phase in milliseconds.
+#[wasm_bindgen(getter_with_clone)]
+pub struct Solution {
+    pub day: u8,
+    /// The answers to part one and part two, empty for a part that failed.
+    pub answers: Vec<String>,
+    /// Why part one and part two failed, empty for a part that has an answer.
+    pub errors: Vec<String>,
+    pub parse_ms: f64,
+    pub part_one_ms: f64,
+    pub part_two_ms: f64
+}
+
+
+/// The puzzles in the registry, ordered by day.
+#[wasm_bindgen]
+pub fn puzzles() -> Vec<Puzzle> {
+    SOLVERS
+        .iter()
+        .map(| solver | Puzzle {
+            day: solver.day(),
+            title: solver.title().to_string(),
+            labels: solver.labels().map(str::to_string).to_vec(),
+            input_file: solver.input_file().to_string()
+        })
+        .collect()
+}
+
+/// Solve the puzzle of a day for the puzzle input text.
+/// Fails if the input cannot be parsed, a part that fails on its own is reported in the errors of the solution.
+/// A panic in the solver is not caught, the WebAssembly instance traps.
+#[wasm_bindgen]
+pub fn solve(day: u8, input: &str) -> Result<Solution, JsError> {
+    let solution = solver(day)?.solve(input).map_err(| error | js_error(day, error))?;
+    let timings = solution.timings;
+
+    let (answers, errors) = solution.answers
+        .into_iter()
+        .map(| answer | match answer {
+            Ok(answer) => (answer, String::new()),
+            Err(error) => (String::new(), format!("Day {day}: {error}"))
+        })
+        .unzip();
+
+    Ok(Solution {
+        day,
+        answers,
+        errors,
+        parse_ms: timings.parse.as_secs_f64() * 1000.0,
+        part_one_ms: timings.part_one.as_secs_f64() * 1000.0,
+        part_two_ms: timings.part_two.as_secs_f64() * 1000.0
+    })
+}
+
+/// Draw what the solver of a day does for part one or two, see [`Solver::render`].
+/// The format is one of the render formats, e.g. "ascii" or "png". Undefined if the puzzle has no grid to draw.
+#[cfg(feature = "render")]
+#[wasm_bindgen]
+pub fn render(day: u8, input: &str, part: u8, format: &str) -> Result<Option<Vec<u8>>, JsError> {
+    let format: crate::render::Format = format.parse().map_err(| error: String | JsError::new(&error))?;
+    let part = match part {
+        2 => crate::Part::Two,
+        _ => crate::Part::One
+    };
+
+    match solver(day)?.render(input, part) {
+        Some(canvas) => {
+            let image = canvas.and_then(| canvas | canvas.render(format)).map_err(| error | js_error(day, error))?;
+            Ok(Some(image))
+        },
+        None => Ok(None)
+    }
+}
+
+
+fn solver(day: u8) -> Result<&'static dyn Solver, JsError> {
+    crate::solver(day).ok_or_else(|| JsError::new(&format!("Day {day} has no solution.")))
+}
+
+fn js_error(day: u8, error: Error) -> JsError {
+    JsError::new(&format!("Day {day}: {error}"))
+}
@@ -0,0 +1,240 @@
+//! The examples from the puzzle texts, from the `example` profile in `./data/example`.
+//! Some parts have their own example, which is in a file with a `_part_2` suffix,
+//! other examples of the same part have a number suffix.
+
+use std::fs;
+use std::path::Path;
+
+use advent_of_code_2023::*;
+
+fn example_path(file_name: &str) -> String {
+    Path::new("./data/example").join(file_name).to_str().unwrap().to_string()
+}
+
+fn example(file_name: &str) -> String {
+    fs::read_to_string(example_path(file_name)).unwrap()
+}
+
+#[test]
+fn test_day_1() {
+    let file = example_path("day_1_trebuchet.txt");
+    let cal_value = day_1_trebuchet::determine_calibration_values(&file).unwrap();
+    assert_eq!(cal_value.0, 142);
+
+    let file = example_path("day_1_trebuchet_part_2.txt");
+    let cal_value = day_1_trebuchet::determine_calibration_values(&file).unwrap();
+    assert_eq!(cal_value.1, 281);
+}
+
+#[test]
+fn test_day_2() {
+    let file = example_path("day_2_cube_conundrum.txt");
+    let (possible_games_sum, cube_power) = day_2_cube_conundrum::determine_games_and_power(&file).unwrap();
+
+    assert_eq!(possible_games_sum, 8);
+    assert_eq!(cube_power, 2286);
+}
+
+#[test]
+fn test_day_3() {
+    let file = example_path("day_3_gear_ratios.txt");
+    let (part_number, gear_ratio) = day_3_gear_ratios::determine_part_number_and_gear_ratio(&file).unwrap();
+
+    assert_eq!(part_number, 4361);
+    assert_eq!(gear_ratio, 467_835);
+}
+
+#[test]
+fn test_day_4() {
+    let file = example_path("day_4_scratchcards.txt");
+    let (points, scratchcards) = day_4_scratchcards::determine_scratchcards_and_points(&file).unwrap();
+
+    assert_eq!(points, 13);
+    assert_eq!(scratchcards, 30);
+}
+
+#[test]
+fn test_day_5() {
+    let file = example_path("day_5_seed_to_location.txt");
+    let closest_location = day_5_seed_soil::determine_closest_location(&file).unwrap();
+
+    assert_eq!(closest_location.0, 35);
+    assert_eq!(closest_location.1, 46);
+}
+
+#[test]
+fn test_day_6() {
+    let file = example_path("day_6_boat_race.txt");
+    let winning_races = day_6_wait_for_it::determine_winning_races(&file).unwrap();
+
+    assert_eq!(winning_races.0, 288);
+    assert_eq!(winning_races.1, 71503);
+}
+
+#[test]
+fn test_day_7() {
+    let file = example_path("day_7_camel_cards.txt");
+    let winnings = day_7_camel_cards::determine_total_winnings(&file).unwrap();
+
+    assert_eq!(winnings.0, 6440);
+    assert_eq!(winnings.1, 5905);
+}
+
+#[test]
+fn test_day_8() {
+    let file = example_path("day_8_haunted_wasteland.txt");
+    let number_of_steps = day_8_haunted_wasteland::determine_steps_through_wasteland(&file).unwrap();
+    assert_eq!(number_of_steps.0, 6);
+
+    // The example of part two has no route from AAA to ZZZ.
+    let data = example("day_8_haunted_wasteland_part_2.txt");
+    let network = day_8_haunted_wasteland::parse(&data).unwrap();
+    assert_eq!(day_8_haunted_wasteland::part_two(&network).unwrap(), 6);
+}
+
+#[test]
+fn test_day_9() {
+    let file = example_path("day_9_mirage_maintenance.txt");
+    let sum_of_values = day_9_mirage_maintenance::determine_sum_of_values(&file).unwrap();
+
+    assert_eq!(sum_of_values.0, 114);
+    assert_eq!(sum_of_values.1, 2);
+}
+
+#[test]
+fn test_day_10() {
+    let file = example_path("day_10_pipe_maze.txt");
+    let (farthest_distance, _) = day_10_pipe_maze::determine_longest_loop_and_enclosed_tiles(&file).unwrap();
+    assert_eq!(farthest_distance, 8);
+
+    let file = example_path("day_10_pipe_maze_part_2.txt");
+    let (_, enclosed) = day_10_pipe_maze::determine_longest_loop_and_enclosed_tiles(&file).unwrap();
+    assert_eq!(enclosed, 10);
+}
+
+#[test]
+fn test_day_11() {
+    let file = example_path("day_11_cosmic_expansion.txt");
+    let sum_of_distances = day_11_cosmic_expansion::determine_sum_of_distances(&file).unwrap();
+
+    assert_eq!(sum_of_distances.0, 374);
+    assert_eq!(sum_of_distances.1, 82_000_210);
+}
+
+#[test]
+fn test_day_12() {
+    let file = example_path("day_12_hot_springs.txt");
+    let sum_of_arrangements = day_12_hot_springs::determine_sum_of_arrangements(&file).unwrap();
+
+    assert_eq!(sum_of_arrangements.0, 21);
+    assert_eq!(sum_of_arrangements.1, 525_152);
+}
+
+#[test]
+fn test_day_13() {
+    let file = example_path("day_13_point_of_incidence.txt");
+    let sum_of_notes = day_13_point_of_incidence::determine_sum_of_notes(&file).unwrap();
+
+    assert_eq!(sum_of_notes.0, 405);
+    assert_eq!(sum_of_notes.1, 400);
+}
+
+#[test]
+fn test_day_14() {
+    let file = example_path("day_14_parabolic_reflector_dish.txt");
+    let total_load = day_14_parabolic_reflector_dish::determine_total_load(&file).unwrap();
+
+    assert_eq!(total_load.0, 136);
+    assert_eq!(total_load.1, 64);
+}
+
+#[test]
+fn test_day_15() {
+    let file = example_path("day_15_lens_library.txt");
+    let hash = day_15_lens_library::determine_hash(&file).unwrap();
+
+    assert_eq!(hash.0, 1320);
+    assert_eq!(hash.1, 145);
+}
+
+#[test]
+fn test_day_16() {
+    let file = example_path("day_16_the_floor_will_be_lava.txt");
+    let energized_tiles = day_16_the_floor_will_be_lava::determine_energized_tiles(&file).unwrap();
+
+    assert_eq!(energized_tiles.0, 46);
+    assert_eq!(energized_tiles.1, 51);
+}
+
+#[test]
+fn test_day_17() {
+    let file = example_path("day_17_clumsy_crucible.txt");
+    let heat_loss = day_17_clumsy_crucible::determine_path_of_minimal_heat_loss(&file).unwrap();
+    assert_eq!(heat_loss.0, 102);
+    assert_eq!(heat_loss.1, 94);
+
+    let file = example_path("day_17_clumsy_crucible_part_2.txt");
+    let heat_loss = day_17_clumsy_crucible::determine_path_of_minimal_heat_loss(&file).unwrap();
+    assert_eq!(heat_loss.1, 71);
+}
+
+#[test]
+fn test_day_18() {
+    let file = example_path("day_18_lavaduct_lagoon.txt");
+    let lava_volume = day_18_lavaduct_lagoon::determine_lava_volume(&file).unwrap();
+
+    assert_eq!(lava_volume.0, 62);
+    assert_eq!(lava_volume.1, 952_408_144_115);
+}
+
+#[test]
+fn test_day_19() {
+    let file = example_path("day_19_aplenty.txt");
+    let rating_numbers = day_19_aplenty::determine_rating_numbers(&file).unwrap();
+
+    assert_eq!(rating_numbers.0, 19114);
+    assert_eq!(rating_numbers.1, 167_409_079_868_000);
+}
+
+#[test]
+fn test_day_20() {
+    // The examples have no "rx" module, so only part one can be checked.
+    for (file_name, pulse_product) in [
+        ("day_20_pulse_propagation.txt", 32_000_000),
+        ("day_20_pulse_propagation_2.txt", 11_687_500)
+    ] {
+        let data = example(file_name);
+        let network = day_20_pulse_propagation::parse(&data).unwrap();
+        assert_eq!(day_20_pulse_propagation::part_one(&network).unwrap(), pulse_product);
+    }
+}
+
+#[test]
+fn test_day_21() {
+    // The puzzle text counts the plots for 6 steps, which is tested within the module, part one takes 64 steps.
+    // The example garden has rocks in the start row and column, so part two has no meaningful answer.
+    let data = example("day_21_step_counter.txt");
+    let garden = day_21_step_counter::parse(&data).unwrap();
+    assert_eq!(day_21_step_counter::part_one(&garden).unwrap(), 42);
+}
+
+#[test]
+fn test_day_22() {
+    let file = example_path("day_22_sand_slabs.txt");
+    let (safe_bricks, fallen_bricks) = day_22_sand_slabs::determine_safe_bricks(&file).unwrap();
+
+    assert_eq!(safe_bricks, 5);
+    assert_eq!(fallen_bricks, 7);
+}
+
+#[test]
+fn test_example_profile_passes_its_manifest() {
+    let profile = inputs::Profile::new("example");
+    let manifest = answers::Manifest::load(&profile.answers_path().unwrap().to_string_lossy()).unwrap();
+
+    for solver in SOLVERS {
+        let mut record = run_day(*solver, Source::Profile(&profile));
+        record.verdicts = Some(manifest.verify(&record));
+        assert!(!record.failed(Part::Both), "day {}: {:?} {:?}", record.day, record.answers, record.verdicts);
+    }
+}
@@ -0,0 +1,89 @@
+//! The fetcher downloads over real HTTP from a stub of the website on localhost.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{env, fs, thread};
+
+use advent_of_code_2023::error::Error;
+use advent_of_code_2023::fetch::{Fetched, Fetcher, UreqHttp};
+use advent_of_code_2023::inputs::Profile;
+use advent_of_code_2023::solver;
+
+/// Serves the given responses in order, one per connection, and sends the request lines and headers back.
+fn stub_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(| line | line.unwrap())
+                .take_while(| line | !line.is_empty())
+                .collect();
+            sender.send(request).unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (base_url, receiver)
+}
+
+fn profile(name: &str) -> Profile {
+    let data_dir = env::temp_dir().join(format!("aoc_2023_test_fetch_{}", std::process::id()));
+    Profile::in_dir(&data_dir, name)
+}
+
+#[test]
+fn test_download_once() {
+    let (base_url, requests) = stub_server(vec![(200, "Time: 7\nDistance: 9\n")]);
+    let profile = profile("download");
+    let solver = solver(6).unwrap();
+    let mut fetcher = Fetcher::new(UreqHttp::default(), &base_url, "secret", Duration::ZERO);
+
+    let file_path = profile.input_path(solver);
+    assert_eq!(fetcher.fetch(solver, &profile).unwrap(), Fetched::Downloaded(file_path.clone()));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "Time: 7\nDistance: 9\n");
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request[0], "GET /2023/day/6/input HTTP/1.1");
+    assert!(request.iter().any(| header | header.eq_ignore_ascii_case("cookie: session=secret")));
+
+    // The stub serves a single response, so a second download would fail.
+    assert_eq!(fetcher.fetch(solver, &profile).unwrap(), Fetched::Cached(file_path));
+    fs::remove_dir_all(profile.dir()).unwrap();
+}
+
+#[test]
+fn test_error_status() {
+    let (base_url, _requests) = stub_server(vec![(500, "Internal Server Error")]);
+    let profile = profile("error");
+    let solver = solver(7).unwrap();
+    let mut fetcher = Fetcher::new(UreqHttp::default(), &base_url, "secret", Duration::ZERO);
+
+    let error = fetcher.fetch(solver, &profile).unwrap_err();
+    assert!(matches!(&error, Error::Download(message) if message.ends_with("returned status 500")));
+    assert!(!profile.input_path(solver).exists());
+}
+
+#[test]
+fn test_rate_limit() {
+    let (base_url, _requests) = stub_server(vec![(200, "1\n"), (200, "2\n")]);
+    let profile = profile("rate_limit");
+    let mut fetcher = Fetcher::new(UreqHttp::default(), &base_url, "secret", Duration::from_millis(300));
+
+    let timer = std::time::Instant::now();
+    for day in [1, 2] {
+        assert!(matches!(fetcher.fetch(solver(day).unwrap(), &profile), Ok(Fetched::Downloaded(_))));
+    }
+    assert!(timer.elapsed() >= Duration::from_millis(300));
+    fs::remove_dir_all(profile.dir()).unwrap();
+}
@@ -0,0 +1,44 @@
+//! The generated puzzle inputs of every day, which must be reproducible and solvable.
+
+use advent_of_code_2023::*;
+use advent_of_code_2023::solver::Solution;
+
+#[test]
+fn test_generated_inputs_are_reproducible() {
+    for day in DAYS {
+        assert_eq!(generate_input(day, 7, 20), generate_input(day, 7, 20), "day {day}");
+        assert_ne!(generate_input(day, 7, 20), generate_input(day, 8, 20), "day {day}");
+    }
+    assert_eq!(generate_input(23, 7, 20), None);
+}
+
+#[test]
+fn test_generated_inputs_are_solvable() {
+    for day in DAYS {
+        let solver = solver(day).unwrap();
+        for seed in 0..5 {
+            for size in [1, 5, 20] {
+                let data = generate_input(day, seed, size).unwrap();
+                if let Err(error) = solver.solve(&data).and_then(Solution::into_answers) {
+                    panic!("day {day} with seed {seed} and size {size}: {error}\n{data}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_large_generated_inputs_are_solvable() {
+    for (day, size) in [(8, 300), (10, 200), (12, 60), (17, 150), (18, 2000), (20, 6), (21, 131), (22, 2000)] {
+        let data = generate_input(day, 1, size).unwrap();
+        assert!(solver(day).unwrap().solve(&data).and_then(Solution::into_answers).is_ok(), "day {day} with size {size}");
+    }
+}
+
+#[test]
+fn test_day_20_counters() {
+    // With the most counters each counter has two bits, and they all reset every third push.
+    let data = generate_input(20, 1, 31).unwrap();
+    let (_, pushes) = day_20_pulse_propagation::solve(&data).unwrap();
+    assert_eq!(pushes, 3);
+}
@@ -0,0 +1,180 @@
+//! Every parser must return an error on malformed input instead of panicking,
+//! and every solver must return an answer or an error within a time limit.
+//! The inputs are the examples with random edits, and random text from the symbols of the puzzles.
+//! The fuzz targets in `./fuzz` do the same with coverage guidance, but need a nightly toolchain.
+
+use std::fs;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use proptest::prelude::*;
+
+use advent_of_code_2023::*;
+use advent_of_code_2023::solver::Solution;
+
+/// Parse an input and drop the result.
+type Parser = fn(&str);
+
+/// The parser of every day.
+const PARSERS: [(u8, Parser); 22] = [
+    (1, | data | { let _ = day_1_trebuchet::parse(data); }),
+    (2, | data | { let _ = day_2_cube_conundrum::parse(data); }),
+    (3, | data | { let _ = day_3_gear_ratios::parse(data); }),
+    (4, | data | { let _ = day_4_scratchcards::parse(data); }),
+    (5, | data | { let _ = day_5_seed_soil::parse(data); }),
+    (6, | data | { let _ = day_6_wait_for_it::parse(data); }),
+    (7, | data | { let _ = day_7_camel_cards::parse(data); }),
+    (8, | data | { let _ = day_8_haunted_wasteland::parse(data); }),
+    (9, | data | { let _ = day_9_mirage_maintenance::parse(data); }),
+    (10, | data | { let _ = day_10_pipe_maze::parse(data); }),
+    (11, | data | { let _ = day_11_cosmic_expansion::parse(data); }),
+    (12, | data | { let _ = day_12_hot_springs::parse(data); }),
+    (13, | data | { let _ = day_13_point_of_incidence::parse(data); }),
+    (14, | data | { let _ = day_14_parabolic_reflector_dish::parse(data); }),
+    (15, | data | { let _ = day_15_lens_library::parse(data); }),
+    (16, | data | { let _ = day_16_the_floor_will_be_lava::parse(data); }),
+    (17, | data | { let _ = day_17_clumsy_crucible::parse(data); }),
+    (18, | data | { let _ = day_18_lavaduct_lagoon::parse(data); }),
+    (19, | data | { let _ = day_19_aplenty::parse(data); }),
+    (20, | data | { let _ = day_20_pulse_propagation::parse(data); }),
+    (21, | data | { let _ = day_21_step_counter::parse(data); }),
+    (22, | data | { let _ = day_22_sand_slabs::parse(data); }),
+];
+
+/// The longest a solver may take on an input, far more than any of the examples need.
+const TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Well-formed inputs that made a parser or a solver panic, abort or hang,
+/// with their answers, or `None` if at least one of the parts has no answer.
+fn regressions() -> Vec<(u8, String, Option<[&'static str; 2]>)> {
+    // Seven maps of twenty overlapping items, which took part two of day 5 exponential time.
+    let maps: String = (0..7)
+        .map(| _ | format!("\nmap:\n{}", (0..20).map(| j | format!("0 {} 1000000000\n", 10 * j)).collect::<String>()))
+        .collect();
+
+    vec![
+        (3, "4294967295*4294967295\n4294967295.........\n".to_string(), None),
+        (5, "seeds: 18446744073709551615 5\n\na-to-b map:\n18446744073709551615 18446744073709551610 100\n".to_string(), None),
+        (5, format!("seeds: 0 100000\n{maps}"), Some(["0", "0"])),
+        (12, format!("{} {}", "?".repeat(100), ["1"; 30].join(",")), None),
+        (12, format!("{} 1", ".".repeat(300_000)), Some(["0", "0"])),
+        (18, "R 9223372036854775807 (#000000)\nD 9223372036854775807 (#000001)\n".to_string(), None),
+        (18, "R -5 (#000000)\n".to_string(), None),
+        (19, "in{a<1:R,b}\nb{x>0:in,A}\n\n".to_string(), None),
+        (19, "in{x>0:in,A}\n\n{x=1,m=2,a=3,s=4}\n".to_string(), None),
+        (20, "broadcaster -> c\n&c -> a\n&a -> b\n&b -> rx\n".to_string(), None),
+        (22, "0,0,1~65535,65535,1\n".to_string(), None),
+        (22, "0,0,1~0,0,65535\n0,0,1~0,0,65535\n".to_string(), None),
+    ]
+}
+
+/// Solve an input on another thread, an error if the solver panicked or did not finish within the time limit.
+/// A solver that hangs keeps its thread busy, but the test fails.
+fn solve_in_time(day: u8, data: String) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = solver(day).unwrap().solve(&data);
+        let _ = sender.send(());
+    });
+
+    match receiver.recv_timeout(TIME_LIMIT) {
+        Ok(()) => Ok(()),
+        Err(RecvTimeoutError::Disconnected) => Err(format!("day {day} panicked")),
+        Err(RecvTimeoutError::Timeout) => Err(format!("day {day} did not finish within {TIME_LIMIT:?}"))
+    }
+}
+
+/// The example input of a day.
+fn example(day: u8) -> String {
+    let solver = solver(day).unwrap();
+    fs::read_to_string(format!("./data/example/{}", solver.input_file())).unwrap()
+}
+
+/// An edit of a text: replace, insert or delete the character at a relative position.
+#[derive(Debug, Clone)]
+enum Edit {
+    Replace(f64, char),
+    Insert(f64, char),
+    Delete(f64)
+}
+
+fn symbol() -> impl Strategy<Value = char> {
+    prop_oneof![
+        prop::sample::select(" \n0123456789-,:;=~#.{}()<>%&|/\\?ABLRSZ".chars().collect::<Vec<char>>()),
+        any::<char>()
+    ]
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        (0.0..1.0, symbol()).prop_map(| (position, c) | Edit::Replace(position, c)),
+        (0.0..1.0, symbol()).prop_map(| (position, c) | Edit::Insert(position, c)),
+        (0.0..1.0).prop_map(Edit::Delete)
+    ]
+}
+
+fn apply(text: &str, edits: &[Edit]) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    for edit in edits {
+        let index = | position: f64, len: usize | ((position * len as f64) as usize).min(len);
+        match *edit {
+            Edit::Replace(position, c) if !chars.is_empty() => {
+                let i = index(position, chars.len() - 1);
+                chars[i] = c;
+            },
+            Edit::Insert(position, c) => {
+                let i = index(position, chars.len());
+                chars.insert(i, c);
+            },
+            Edit::Delete(position) if !chars.is_empty() => {
+                let i = index(position, chars.len() - 1);
+                chars.remove(i);
+            },
+            _ => {}
+        }
+    }
+    chars.into_iter().collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(5000))]
+
+    #[test]
+    fn test_parse_edited_examples(day in 1..=22_u8, edits in prop::collection::vec(edit(), 1..8)) {
+        let (_, parse) = PARSERS[day as usize - 1];
+        parse(&apply(&example(day), &edits));
+    }
+
+    #[test]
+    fn test_parse_random_text(day in 1..=22_u8, text in prop::collection::vec(symbol(), 0..60)) {
+        let (_, parse) = PARSERS[day as usize - 1];
+        parse(&text.into_iter().collect::<String>());
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn test_solve_edited_examples(day in 1..=22_u8, edits in prop::collection::vec(edit(), 1..8)) {
+        solve_in_time(day, apply(&example(day), &edits)).map_err(TestCaseError::fail)?;
+    }
+}
+
+#[test]
+fn test_regressions() {
+    for (day, data, answers) in regressions() {
+        let (_, parse) = PARSERS[day as usize - 1];
+        parse(&data);
+        assert_eq!(solve_in_time(day, data.clone()), Ok(()), "{data:?}");
+        let solution = solver(day).unwrap().solve(&data).and_then(Solution::into_answers).ok();
+        assert_eq!(solution, answers.map(| answers | answers.map(String::from)), "{data:?}");
+    }
+}
+
+#[test]
+fn test_parser_table_is_ordered_by_day() {
+    let days: Vec<u8> = PARSERS.iter().map(| &(day, _) | day).collect();
+    assert_eq!(days, DAYS.collect::<Vec<u8>>());
+}
@@ -0,0 +1,134 @@
+//! The drawings and animations of the example inputs agree with the answers of the solvers and the puzzle texts.
+
+use std::fs;
+
+use advent_of_code_2023::{Part, solver};
+use advent_of_code_2023::render::{Canvas, Frames, Style};
+
+fn example(file_name: &str) -> String {
+    fs::read_to_string(format!("./data/example/{file_name}")).unwrap()
+}
+
+/// The answers to the example of a day and the drawings of part one and two.
+fn render(day: u8, file_name: &str) -> ([usize; 2], [Canvas; 2]) {
+    let data = example(file_name);
+    let solver = solver(day).unwrap();
+
+    let answers = solver.solve(&data).unwrap().into_answers().unwrap().map(| answer | answer.parse().unwrap());
+    let canvases = [Part::One, Part::Two].map(| part | solver.render(&data, part).unwrap().unwrap());
+    (answers, canvases)
+}
+
+#[test]
+fn test_day_10() {
+    let ([farthest, _], [canvas, _]) = render(10, "day_10_pipe_maze.txt");
+    assert_eq!(canvas.count(Style::Primary) + canvas.count(Style::Accent), 2 * farthest);
+
+    let ([_, enclosed], [_, canvas]) = render(10, "day_10_pipe_maze_part_2.txt");
+    assert_eq!(canvas.count(Style::Secondary), enclosed);
+}
+
+#[test]
+fn test_day_14() {
+    let (loads, canvases) = render(14, "day_14_parabolic_reflector_dish.txt");
+    let tilted = "\
+        OOOO.#.O..\nOO..#....#\nOO..O##..O\nO..#.OO...\n........#.\n\
+        ..#....#.#\n..O..#.O.O\n..O.......\n#....###..\n#....#....\n";
+    assert_eq!(canvases[0].tiles.to_text(| tile | tile.symbol), tilted);
+
+    for (load, canvas) in loads.iter().zip(&canvases) {
+        assert!(canvas.caption.ends_with(&format!("a total load of {load}.")));
+    }
+}
+
+#[test]
+fn test_day_16() {
+    let (energized, canvases) = render(16, "day_16_the_floor_will_be_lava.txt");
+    for (energized, canvas) in energized.iter().zip(&canvases) {
+        // The tile where the beam enters is drawn as the direction of the beam.
+        assert_eq!(canvas.count(Style::Primary) + canvas.count(Style::Accent), *energized);
+    }
+}
+
+#[test]
+fn test_day_17() {
+    let data = example("day_17_clumsy_crucible.txt");
+    let (heat_losses, canvases) = render(17, "day_17_clumsy_crucible.txt");
+
+    // The heat loss is that of the blocks on the path, except for the first block.
+    let heat_loss: Vec<u32> = data.chars().filter(| c | c.is_ascii_digit()).map(| c | c.to_digit(10).unwrap()).collect();
+    for (expected, canvas) in heat_losses.iter().zip(&canvases) {
+        let path_heat_loss: u32 = canvas.tiles
+            .iter()
+            .zip(&heat_loss)
+            .filter(| ((_, tile), _) | tile.style == Style::Primary)
+            .map(| (_, heat_loss) | heat_loss)
+            .sum();
+        assert_eq!(path_heat_loss as usize, *expected);
+    }
+}
+
+#[test]
+fn test_day_21() {
+    let ([plots, _], [canvas, garden]) = render(21, "day_21_step_counter.txt");
+    assert_eq!(canvas.count(Style::Primary), plots);
+
+    // Part two draws the five by five gardens around the start.
+    assert_eq!(garden.tiles.width(), 5 * canvas.tiles.width());
+    assert_eq!(garden.tiles.height(), 5 * canvas.tiles.height());
+}
+
+#[test]
+fn test_days_without_a_grid() {
+    assert!(solver(5).unwrap().render(&example("day_5_seed_to_location.txt"), Part::One).is_none());
+}
+
+
+/// The frames of the example of a day.
+fn animate(day: u8, file_name: &str, part: Part, frames: Frames) -> Vec<Canvas> {
+    solver(day).unwrap().animate(&example(file_name), part, frames).unwrap().unwrap()
+}
+
+#[test]
+fn test_animate_day_14() {
+    let frames = animate(14, "day_14_parabolic_reflector_dish.txt", Part::One, Frames::new(0..=10, 1));
+    assert_eq!(frames.len(), 2);
+    assert!(frames[1].caption.ends_with("the total load is 136."));
+
+    // The third spin cycle from the puzzle text.
+    let frames = animate(14, "day_14_parabolic_reflector_dish.txt", Part::Two, Frames::new(4..=12, 4));
+    let after_three_cycles = "\
+        .....#....\n....#...O#\n.....##...\n..O#......\n.....OOO#.\n\
+        .O#...O#.#\n....O#...O\n.......OOO\n#...O###.O\n#.OOO#...O\n";
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[2].tiles.to_text(| tile | tile.symbol), after_three_cycles);
+}
+
+#[test]
+fn test_animate_day_22() {
+    let frames = animate(22, "day_22_sand_slabs.txt", Part::One, Frames::new(0..=100, 1));
+    assert_eq!(frames.len(), 8);
+
+    // The settled bricks seen along the y axis, like in the puzzle text.
+    let settled = frames[7].tiles.to_text(| tile | tile.symbol);
+    let rows: Vec<&str> = settled.lines().rev().take(7).map(| row | &row[..3]).collect();
+    assert_eq!(rows, ["---", ".#.", "###", "#.#", "###", ".#.", ".#."]);
+    assert!(frames[7].caption.ends_with("7 of 7 bricks settled, 5 of them fell."));
+
+    // A single brick high above the ground would take a canvas of a hundred million tiles.
+    let solver = solver(22).unwrap();
+    assert!(solver.animate("999,999,65534~999,999,65534\n", Part::One, Frames::new(0..=1, 1)).unwrap().is_err());
+}
+
+#[test]
+fn test_animate_day_20() {
+    let frames = animate(20, "day_20_pulse_propagation.txt", Part::One, Frames::new(0..=1000, 250));
+    assert_eq!(frames.len(), 5);
+    assert!(frames[4].caption.ends_with("8000 low and 4000 high pulses sent."));
+}
+
+#[test]
+fn test_days_without_a_simulation() {
+    let frames = Frames::new(0..=10, 1);
+    assert!(solver(10).unwrap().animate(&example("day_10_pipe_maze.txt"), Part::One, frames).is_none());
+}
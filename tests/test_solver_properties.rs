@@ -0,0 +1,315 @@
+//! Differential tests of the solvers that rely on a shortcut.
+//! Each day has a generator of small random puzzle inputs and a naive reference implementation,
+//! proptest compares the two on many cases and shrinks any counterexample it finds.
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+
+use advent_of_code_2023::*;
+
+const CASES: u32 = 2000;
+
+
+// Day 5: the boundary search of part two against mapping every seed.
+
+/// A map group as (destination, source, length) items.
+type MapGroup = Vec<(usize, usize, usize)>;
+
+/// A map group without overlapping sources or destinations.
+fn map_group() -> impl Strategy<Value = MapGroup> {
+    prop::collection::vec((0..8_usize, 1..10_usize, 0..8_usize), 0..5)
+        .prop_flat_map(| items | {
+            let order: Vec<usize> = (0..items.len()).collect();
+            (Just(items), Just(order).prop_shuffle())
+        })
+        .prop_map(| (items, order) | {
+            // Lay out the sources in the generated order and the destinations in the shuffled order.
+            let mut sources: Vec<usize> = Vec::new();
+            let mut position: usize = 0;
+            for &(gap, length, _) in &items {
+                sources.push(position + gap);
+                position += gap + length;
+            }
+
+            let mut destinations: Vec<usize> = vec![0; items.len()];
+            let mut position: usize = 0;
+            for &index in &order {
+                let (_, length, gap) = items[index];
+                destinations[index] = position + gap;
+                position += gap + length;
+            }
+
+            items
+                .iter()
+                .enumerate()
+                .map(| (index, &(_, length, _)) | (destinations[index], sources[index], length))
+                .collect()
+        })
+}
+
+fn almanac() -> impl Strategy<Value = (Vec<(usize, usize)>, Vec<MapGroup>)> {
+    (
+        prop::collection::vec((0..60_usize, 1..15_usize), 1..4),
+        prop::collection::vec(map_group(), 1..5)
+    )
+}
+
+fn almanac_text(seeds: &[(usize, usize)], groups: &[MapGroup]) -> String {
+    let seeds: Vec<String> = seeds.iter().map(| (start, length) | format!("{start} {length}")).collect();
+    let mut text = format!("seeds: {}\n", seeds.join(" "));
+    for (index, group) in groups.iter().enumerate() {
+        text += &format!("\nlevel{index}-to-level{} map:\n", index + 1);
+        for (destination, source, length) in group {
+            text += &format!("{destination} {source} {length}\n");
+        }
+    }
+    text
+}
+
+fn naive_location(groups: &[MapGroup], seed: usize) -> usize {
+    groups.iter().fold(seed, | value, group | {
+        group
+            .iter()
+            .find(| &&(_, source, length) | source <= value && value < source + length)
+            .map_or(value, | &(destination, source, _) | destination + value - source)
+    })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_day_5((seeds, groups) in almanac()) {
+        let (closest_location_1, closest_location_2) = day_5_seed_soil::solve(&almanac_text(&seeds, &groups)).unwrap();
+
+        let naive_1 = seeds
+            .iter()
+            .flat_map(| &(start, length) | [start, length])
+            .map(| seed | naive_location(&groups, seed))
+            .min();
+        let naive_2 = seeds
+            .iter()
+            .flat_map(| &(start, length) | start..start + length)
+            .map(| seed | naive_location(&groups, seed))
+            .min();
+
+        prop_assert_eq!(Some(closest_location_1), naive_1.map(| location | location as u64));
+        prop_assert_eq!(Some(closest_location_2), naive_2.map(| location | location as u64));
+    }
+}
+
+
+// Day 6: the roots of the quadratic against trying every time the button can be held.
+
+fn naive_ways_to_win(time: u64, distance: u64) -> u64 {
+    (0..=time).filter(| held | held * (time - held) > distance).count() as u64
+}
+
+fn concatenate(values: &[u64]) -> u64 {
+    values.iter().map(| value | value.to_string()).collect::<String>().parse().unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_day_6(races in prop::collection::vec((1..60_u64, 0..1000_u64), 1..3)) {
+        let (times, distances): (Vec<u64>, Vec<u64>) = races.iter().copied().unzip();
+        let text = format!(
+            "Time: {}\nDistance: {}\n",
+            times.iter().map(u64::to_string).collect::<Vec<_>>().join(" "),
+            distances.iter().map(u64::to_string).collect::<Vec<_>>().join(" ")
+        );
+        let (ways_to_win_1, ways_to_win_2) = day_6_wait_for_it::solve(&text).unwrap();
+
+        let naive_1: u64 = races.iter().map(| &(time, distance) | naive_ways_to_win(time, distance)).product();
+        let naive_2 = naive_ways_to_win(concatenate(&times), concatenate(&distances));
+
+        prop_assert_eq!(ways_to_win_1 as u64, naive_1);
+        prop_assert_eq!(ways_to_win_2 as u64, naive_2);
+    }
+}
+
+
+// Day 9: the fitted polynomial against repeatedly taking differences.
+
+fn naive_next_value(values: &[i64]) -> i64 {
+    if values.iter().all(| &value | value == 0) {
+        return 0;
+    }
+    let differences: Vec<i64> = values.windows(2).map(| pair | pair[1] - pair[0]).collect();
+    values[values.len() - 1] + naive_next_value(&differences)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_day_9(
+        histories in prop::collection::vec(
+            (prop::collection::vec(-9..10_i64, 1..5), 1..12_usize), 1..5
+        )
+    ) {
+        // Histories are the values of a random polynomial, at least as long as needed to fit it.
+        let histories: Vec<Vec<i64>> = histories
+            .iter()
+            .map(| (coefficients, extra) | {
+                (0..coefficients.len() + extra)
+                    .map(| x | coefficients.iter().rev().fold(0, | value, &c | value * x as i64 + c))
+                    .collect()
+            })
+            .collect();
+
+        let text: String = histories
+            .iter()
+            .map(| values | values.iter().map(i64::to_string).collect::<Vec<_>>().join(" ") + "\n")
+            .collect();
+        let (sum_of_values_1, sum_of_values_2) = day_9_mirage_maintenance::solve(&text).unwrap();
+
+        let naive_1: i64 = histories.iter().map(| values | naive_next_value(values)).sum();
+        let naive_2: i64 = histories
+            .iter()
+            .map(| values | naive_next_value(&values.iter().rev().copied().collect::<Vec<_>>()))
+            .sum();
+
+        prop_assert_eq!(sum_of_values_1, naive_1);
+        prop_assert_eq!(sum_of_values_2, naive_2);
+    }
+}
+
+
+// Day 11: the prefix sums over each axis against the distance of every pair of galaxies.
+
+fn naive_sum_of_distances(image: &[Vec<bool>], expansion: usize) -> usize {
+    let galaxies: Vec<(usize, usize)> = image
+        .iter()
+        .enumerate()
+        .flat_map(| (y, row) | row.iter().enumerate().filter(| (_, &galaxy) | galaxy).map(move | (x, _) | (x, y)))
+        .collect();
+    let empty_rows: Vec<usize> = (0..image.len()).filter(| &y | !image[y].contains(&true)).collect();
+    let empty_columns: Vec<usize> = (0..image[0].len()).filter(| &x | image.iter().all(| row | !row[x])).collect();
+
+    let mut total: usize = 0;
+    for (i, &(x_1, y_1)) in galaxies.iter().enumerate() {
+        for &(x_2, y_2) in &galaxies[i + 1..] {
+            let (x_range, y_range) = (x_1.min(x_2)..x_1.max(x_2), y_1.min(y_2)..y_1.max(y_2));
+            let expanded = empty_columns.iter().filter(| x | x_range.contains(x)).count()
+                + empty_rows.iter().filter(| y | y_range.contains(y)).count();
+            total += x_range.len() + y_range.len() + expanded * (expansion - 1);
+        }
+    }
+    total
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_day_11(
+        image in (1..12_usize, 1..12_usize).prop_flat_map(| (width, height) | {
+            prop::collection::vec(prop::collection::vec(prop::bool::weighted(0.15), width), height)
+        })
+    ) {
+        let text: String = image
+            .iter()
+            .map(| row | row.iter().map(| &galaxy | if galaxy { '#' } else { '.' }).collect::<String>() + "\n")
+            .collect();
+        let (sum_of_distances_1, sum_of_distances_2) = day_11_cosmic_expansion::solve(&text).unwrap();
+
+        prop_assert_eq!(sum_of_distances_1, naive_sum_of_distances(&image, 2) as u64);
+        prop_assert_eq!(sum_of_distances_2, naive_sum_of_distances(&image, 1_000_000) as u64);
+    }
+}
+
+
+// Day 18: the shoelace formula against digging the trench and flood filling the outside.
+
+/// A lagoon without holes: column x spans the rows top[x] to bottom[x] of the trench.
+/// The bottom is below the tops of the neighbouring columns, so that the trench never touches itself.
+fn lagoon() -> impl Strategy<Value = Vec<(char, i64)>> {
+    prop::collection::vec((-5..5_i64, 1..4_i64), 1..10).prop_map(| columns | {
+        let top: Vec<i64> = columns.iter().map(| &(top, _) | top).collect();
+        let bottom: Vec<i64> = (0..columns.len())
+            .map(| x | top[x.saturating_sub(2)..(x + 3).min(top.len())].iter().max().unwrap() + columns[x].1)
+            .collect();
+
+        let mut plan: Vec<(char, i64)> = Vec::new();
+        let mut step = | direction: char, distance: i64 | match distance {
+            0 => {},
+            d if d > 0 => plan.push((direction, d)),
+            d => plan.push((if direction == 'D' { 'U' } else { 'D' }, -d))
+        };
+
+        // Along the top from left to right, down the right side, back along the bottom and up the left side.
+        for x in 1..columns.len() {
+            step('R', 1);
+            step('D', top[x] - top[x - 1]);
+        }
+        step('D', bottom[columns.len() - 1] - top[columns.len() - 1]);
+        for x in (1..columns.len()).rev() {
+            step('L', 1);
+            step('D', bottom[x - 1] - bottom[x]);
+        }
+        step('U', bottom[0] - top[0]);
+        plan
+    })
+}
+
+fn naive_lagoon_volume(plan: &[(char, i64)]) -> usize {
+    let mut trench: HashSet<(i64, i64)> = HashSet::from([(0, 0)]);
+    let mut position = (0, 0);
+    for &(direction, distance) in plan {
+        let step = match direction {
+            'U' => (0, -1),
+            'D' => (0, 1),
+            'L' => (-1, 0),
+            _ => (1, 0)
+        };
+        for _ in 0..distance {
+            position = (position.0 + step.0, position.1 + step.1);
+            trench.insert(position);
+        }
+    }
+
+    // Flood fill the outside within a border around the trench.
+    let min_x = trench.iter().map(| p | p.0).min().unwrap() - 1;
+    let max_x = trench.iter().map(| p | p.0).max().unwrap() + 1;
+    let min_y = trench.iter().map(| p | p.1).min().unwrap() - 1;
+    let max_y = trench.iter().map(| p | p.1).max().unwrap() + 1;
+
+    let mut outside: HashSet<(i64, i64)> = HashSet::from([(min_x, min_y)]);
+    let mut queue: Vec<(i64, i64)> = vec![(min_x, min_y)];
+    while let Some((x, y)) = queue.pop() {
+        for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+            if (min_x..=max_x).contains(&next.0) && (min_y..=max_y).contains(&next.1)
+                && !trench.contains(&next) && outside.insert(next) {
+                queue.push(next);
+            }
+        }
+    }
+
+    ((max_x - min_x + 1) * (max_y - min_y + 1)) as usize - outside.len()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn test_day_18(plan in lagoon()) {
+        // The color encodes the same instruction as the direction and distance.
+        let text: String = plan
+            .iter()
+            .map(| &(direction, distance) | {
+                let code = match direction { 'R' => 0, 'D' => 1, 'L' => 2, _ => 3 };
+                format!("{direction} {distance} (#{distance:05x}{code})\n")
+            })
+            .collect();
+        let (lava_volume_1, lava_volume_2) = day_18_lavaduct_lagoon::solve(&text).unwrap();
+
+        let naive = naive_lagoon_volume(&plan);
+        prop_assert_eq!(lava_volume_1 as usize, naive);
+        prop_assert_eq!(lava_volume_2 as usize, naive);
+    }
+}
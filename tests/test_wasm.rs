@@ -0,0 +1,52 @@
+//! The JavaScript API, run with `cargo test --target wasm32-unknown-unknown`, see the README.
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::*;
+
+use advent_of_code_2023::wasm;
+
+const BOAT_RACES: &str = include_str!("../data/example/day_6_boat_race.txt");
+const HAUNTED_WASTELAND: &str = include_str!("../data/example/day_8_haunted_wasteland_part_2.txt");
+const PIPE_MAZE: &str = include_str!("../data/example/day_10_pipe_maze.txt");
+
+#[wasm_bindgen_test]
+fn test_puzzles() {
+    let puzzles = wasm::puzzles();
+    assert_eq!(puzzles.len(), 22);
+    assert_eq!(puzzles[5].day, 6);
+    assert_eq!(puzzles[5].input_file, "day_6_boat_race.txt");
+    assert_eq!(puzzles[5].labels.len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_solve() {
+    let solution = wasm::solve(6, BOAT_RACES).unwrap();
+    assert_eq!(solution.answers, ["288", "71503"]);
+    assert_eq!(solution.errors, ["", ""]);
+    assert!(solution.parse_ms >= 0.0 && solution.part_two_ms >= 0.0);
+
+    // The answers of the personal inputs of some days do not fit in a 32-bit usize.
+    let solution = wasm::solve(12, "?###???????? 3,2,1\n").unwrap();
+    assert_eq!(solution.answers, ["10", "506250"]);
+    let solution = wasm::solve(11, &"#.\n..\n".repeat(50)).unwrap();
+    assert_eq!(solution.answers[1], "20825020825");
+}
+
+#[wasm_bindgen_test]
+fn test_unsolvable() {
+    assert!(wasm::solve(8, "").is_err());
+    assert!(wasm::solve(25, BOAT_RACES).is_err());
+    // The example of part two has no node AAA to start part one from, but part two still has an answer.
+    let solution = wasm::solve(8, HAUNTED_WASTELAND).unwrap();
+    assert_eq!(solution.answers, ["", "6"]);
+    assert_eq!(solution.errors[0], "Day 8: no solution: node AAA is not in the map");
+}
+
+#[wasm_bindgen_test]
+fn test_render() {
+    let text = String::from_utf8(wasm::render(10, PIPE_MAZE, 1, "ascii").unwrap().unwrap()).unwrap();
+    assert!(text.contains('S'));
+    assert!(wasm::render(10, PIPE_MAZE, 1, "png").unwrap().unwrap().starts_with(b"\x89PNG"));
+    assert!(wasm::render(6, BOAT_RACES, 1, "ascii").unwrap().is_none());
+    assert!(wasm::render(10, PIPE_MAZE, 1, "gif").is_err());
+}